@@ -0,0 +1,444 @@
+use crate::models::{anomaly::*, attendance::{AttendanceRecord, AttendanceStatus}, employee::Employee, dates::{days_in_month, format_date}};
+use crate::commands::attendance::cached_working_days;
+use crate::commands::holiday::is_weekend;
+use crate::commands::settings::load_retirement_settings;
+use crate::commands::print::{generate_html_anomaly_report, PrintOptions};
+use crate::database::{Collections, helpers::*};
+use crate::AppState;
+use mongodb::bson::{doc, RawDocumentBuf};
+use mongodb::options::FindOptions;
+use tauri::State;
+use futures::stream::TryStreamExt;
+use chrono::{Datelike, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Working days absent in a row before an employee is flagged, unless a limit is given
+const DEFAULT_MAX_CONSECUTIVE_ABSENCES: i32 = 3;
+
+/// Scan the attendance records of a period for anomalies and write them to an HTML report
+#[tauri::command]
+pub async fn detect_attendance_anomalies(
+    state: State<'_, AppState>,
+    from_month: i32,
+    from_year: i32,
+    to_month: i32,
+    to_year: i32,
+    max_consecutive_absences: Option<i32>,
+    options: PrintOptions,
+) -> Result<AnomalyReportResponse, String> {
+    let db = &state.db;
+    let employee_collection = db.collection::<Employee>(Collections::EMPLOYEES);
+    let attendance_collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+
+    let max_absences = max_consecutive_absences.unwrap_or(DEFAULT_MAX_CONSECUTIVE_ABSENCES).max(1);
+
+    let first = NaiveDate::from_ymd_opt(from_year, from_month as u32, 1);
+    let last = NaiveDate::from_ymd_opt(to_year, to_month as u32, 1);
+    let (first, last) = match (first, last) {
+        (Some(first), Some(last)) if first <= last => (first, last),
+        _ => return Ok(AnomalyReportResponse {
+            success: false,
+            data: None,
+            file_path: None,
+            message: None,
+            error: Some("Invalid period. The start month must be on or before the end month".to_string()),
+        }),
+    };
+
+    let mut months = Vec::new();
+    let mut month_start = first;
+    while month_start <= last {
+        months.push(month_start);
+        month_start = next_month(month_start);
+    }
+
+    let today = chrono::Local::now().date_naive();
+    let retirement_settings = load_retirement_settings(db).await?;
+
+    // Load employees
+    let mut cursor = employee_collection.clone_with_type::<RawDocumentBuf>().find(doc! {}, None)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut employees = Vec::new();
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        employee.derive_dates(&retirement_settings, today);
+        if let Some(id) = employee.id.as_ref() {
+            employees.push((object_id_to_string(id), employee));
+        }
+    }
+
+    // Load the period's attendance records by employee, in month order
+    let find_options = FindOptions::builder()
+        .sort(doc! { "year": 1, "month": 1 })
+        .build();
+
+    let mut cursor = attendance_collection.find(
+        doc! { "year": { "$gte": from_year, "$lte": to_year } },
+        find_options
+    ).await
+        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+
+    let mut records_by_employee: HashMap<String, Vec<AttendanceRecord>> = HashMap::new();
+    while let Some(record) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        let in_period = NaiveDate::from_ymd_opt(record.year, record.month as u32, 1)
+            .map(|start| start >= first && start <= last)
+            .unwrap_or(false);
+        if in_period {
+            records_by_employee.entry(record.employee_id.clone()).or_default().push(record);
+        }
+    }
+
+    let mut working_days_cache = HashMap::new();
+    let mut anomalies = Vec::new();
+
+    for (employee_id, employee) in &employees {
+        let records = records_by_employee.remove(employee_id).unwrap_or_default();
+        let anomaly = |anomaly_type: AnomalyType, month: i32, year: i32, dates: Vec<String>, description: String| {
+            AttendanceAnomaly {
+                anomaly_type,
+                employee_id: employee_id.clone(),
+                employee_number: employee.employee_number.clone(),
+                full_name: employee.full_name.clone(),
+                month,
+                year,
+                dates,
+                description,
+            }
+        };
+
+        let service_start = employee.date_of_arrival_vds.unwrap_or(employee.first_appointment_date);
+        let retired = employee.retired_date;
+        let transferred = employee.date_of_transfer;
+
+        // Months the employee was in service without a record. Archived staff have left.
+        for month_start in &months {
+            let in_service = !employee.archived
+                && in_service(*month_start, today, service_start, retired, transferred);
+            let recorded = records.iter()
+                .any(|r| r.year == month_start.year() && r.month == month_start.month() as i32);
+
+            if in_service && !recorded {
+                anomalies.push(anomaly(
+                    AnomalyType::MissingRecord,
+                    month_start.month() as i32,
+                    month_start.year(),
+                    Vec::new(),
+                    "No attendance record for the month".to_string(),
+                ));
+            }
+        }
+
+        let mut holidays_by_month: HashMap<(i32, i32), HashSet<i32>> = HashMap::new();
+
+        for record in &records {
+            let (month, year) = (record.month, record.year);
+            if NaiveDate::from_ymd_opt(year, month as u32, 1).is_none() {
+                continue;
+            }
+            let length = days_in_month(year, month as u32).unwrap_or(0) as i32;
+
+            let mut day_counts: BTreeMap<i32, usize> = BTreeMap::new();
+            for daily in &record.records {
+                *day_counts.entry(daily.date).or_insert(0) += 1;
+            }
+
+            let duplicates: Vec<String> = day_counts.iter()
+                .filter(|(day, count)| **count > 1 && **day >= 1 && **day <= length)
+                .filter_map(|(day, _)| NaiveDate::from_ymd_opt(year, month as u32, *day as u32))
+                .map(|date| format_date(&date))
+                .collect();
+            if !duplicates.is_empty() {
+                anomalies.push(anomaly(
+                    AnomalyType::DuplicateDay,
+                    month,
+                    year,
+                    duplicates,
+                    "Days entered more than once".to_string(),
+                ));
+            }
+
+            let invalid: Vec<String> = day_counts.keys()
+                .filter(|day| **day < 1 || **day > length)
+                .map(|day| day.to_string())
+                .collect();
+            if !invalid.is_empty() {
+                anomalies.push(anomaly(
+                    AnomalyType::InvalidDay,
+                    month,
+                    year,
+                    invalid,
+                    format!("Days outside the {} days of the month", length),
+                ));
+            }
+
+            // Attendance recorded once the employee has left
+            let recorded_after = |cutoff: NaiveDate| -> Vec<String> {
+                record.records.iter()
+                    .filter(|d| !matches!(d.status,
+                        AttendanceStatus::Absent | AttendanceStatus::PublicHoliday | AttendanceStatus::Weekend))
+                    .filter_map(|d| NaiveDate::from_ymd_opt(year, month as u32, d.date as u32))
+                    .filter(|date| *date > cutoff)
+                    .map(|date| format_date(&date))
+                    .collect()
+            };
+            for (cutoff, anomaly_type, event) in [
+                (retired, AnomalyType::AfterRetirement, "retirement"),
+                (transferred, AnomalyType::AfterTransfer, "transfer"),
+            ] {
+                if let Some(cutoff) = cutoff {
+                    let dates = recorded_after(cutoff);
+                    if !dates.is_empty() {
+                        anomalies.push(anomaly(
+                            anomaly_type,
+                            month,
+                            year,
+                            dates,
+                            format!("Attendance recorded after the {} date {}", event, format_date(&cutoff)),
+                        ));
+                    }
+                }
+            }
+
+            let working_days = cached_working_days(db, &mut working_days_cache, month, year).await?;
+            holidays_by_month.insert(
+                (year, month),
+                working_days.holidays.iter().map(|h| h.day).collect(),
+            );
+        }
+
+        for run in absence_runs(&records, &holidays_by_month, today, max_absences) {
+            let start = run[0];
+            anomalies.push(anomaly(
+                AnomalyType::ConsecutiveAbsence,
+                start.month() as i32,
+                start.year(),
+                run.iter().map(format_date).collect(),
+                format!("Absent for {} working days in a row without leave", run.len()),
+            ));
+        }
+    }
+
+    anomalies.sort_by(|a, b| {
+        (&a.employee_number, a.year, a.month, a.anomaly_type)
+            .cmp(&(&b.employee_number, b.year, b.month, b.anomaly_type))
+    });
+
+    let report = AnomalyReport {
+        from_month,
+        from_year,
+        to_month,
+        to_year,
+        max_consecutive_absences: max_absences,
+        anomalies,
+    };
+
+    let print_response = generate_html_anomaly_report(&report, options).await?;
+
+    Ok(AnomalyReportResponse {
+        success: true,
+        message: Some(format!("Found {} attendance anomalies", report.anomalies.len())),
+        data: Some(report),
+        file_path: print_response.file_path,
+        error: print_response.error,
+    })
+}
+
+// Helper functions for anomaly detection
+
+/// Whether an employee was in service for any part of the month up to today
+fn in_service(
+    month_start: NaiveDate,
+    today: NaiveDate,
+    service_start: NaiveDate,
+    retired: Option<NaiveDate>,
+    transferred: Option<NaiveDate>,
+) -> bool {
+    month_start <= today
+        && service_start <= last_day_of_month(month_start)
+        && retired.map(|d| d >= month_start).unwrap_or(true)
+        && transferred.map(|d| d >= month_start).unwrap_or(true)
+}
+
+/// Runs of absences longer than allowed, from records in month order. Weekends, holidays
+/// and days still to come neither extend nor break a run; a month without a record breaks it.
+fn absence_runs(
+    records: &[AttendanceRecord],
+    holidays_by_month: &HashMap<(i32, i32), HashSet<i32>>,
+    today: NaiveDate,
+    max_absences: i32,
+) -> Vec<Vec<NaiveDate>> {
+    let mut runs = Vec::new();
+    let mut run: Vec<NaiveDate> = Vec::new();
+    let mut previous_month: Option<NaiveDate> = None;
+    let no_holidays = HashSet::new();
+
+    for record in records {
+        let month_start = match NaiveDate::from_ymd_opt(record.year, record.month as u32, 1) {
+            Some(date) => date,
+            None => continue,
+        };
+
+        if previous_month.map(|p| next_month(p) != month_start).unwrap_or(false) {
+            flush_absences(&mut run, max_absences, &mut runs);
+        }
+        previous_month = Some(month_start);
+
+        let holiday_days = holidays_by_month.get(&(record.year, record.month)).unwrap_or(&no_holidays);
+
+        for date in month_start.iter_days().take_while(|d| d.month() == month_start.month()) {
+            let day = date.day() as i32;
+            match record.records.iter().find(|d| d.date == day).map(|d| d.status) {
+                Some(AttendanceStatus::Absent) => run.push(date),
+                Some(AttendanceStatus::Weekend) | Some(AttendanceStatus::PublicHoliday) => {},
+                None if is_weekend(date) || holiday_days.contains(&day) || date > today => {},
+                _ => flush_absences(&mut run, max_absences, &mut runs),
+            }
+        }
+    }
+    flush_absences(&mut run, max_absences, &mut runs);
+
+    runs
+}
+
+/// Keep a run of absences longer than allowed, then start a new run
+fn flush_absences(run: &mut Vec<NaiveDate>, max_absences: i32, runs: &mut Vec<Vec<NaiveDate>>) {
+    if run.len() as i32 > max_absences {
+        runs.push(std::mem::take(run));
+    } else {
+        run.clear();
+    }
+}
+
+fn next_month(month_start: NaiveDate) -> NaiveDate {
+    let (year, month) = if month_start.month() == 12 {
+        (month_start.year() + 1, 1)
+    } else {
+        (month_start.year(), month_start.month() + 1)
+    };
+    NaiveDate::from_ymd_opt(year, month, 1).unwrap_or(month_start)
+}
+
+fn last_day_of_month(month_start: NaiveDate) -> NaiveDate {
+    next_month(month_start).pred_opt().unwrap_or(month_start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::attendance::DailyAttendance;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    /// A month with weekends marked, the given days absent, the unrecorded days left out
+    /// and every other day present
+    fn record(year: i32, month: i32, absent: &[i32], unrecorded: &[i32]) -> AttendanceRecord {
+        let month_start = date(year, month as u32, 1);
+        let records = month_start.iter_days()
+            .take_while(|d| d.month() == month_start.month())
+            .filter(|d| !unrecorded.contains(&(d.day() as i32)))
+            .map(|d| {
+                let day = d.day() as i32;
+                let status = if is_weekend(d) {
+                    AttendanceStatus::Weekend
+                } else if absent.contains(&day) {
+                    AttendanceStatus::Absent
+                } else {
+                    AttendanceStatus::Present
+                };
+                DailyAttendance {
+                    date: day,
+                    status,
+                    notes: None,
+                    leave_application_id: None,
+                    previous_status: None,
+                    check_in: None,
+                    check_out: None,
+                    is_late: false,
+                    is_early_departure: false,
+                    short_leave: false,
+                }
+            })
+            .collect();
+
+        AttendanceRecord {
+            id: None,
+            employee_id: "employee".to_string(),
+            employee_number: "E001".to_string(),
+            month,
+            year,
+            records,
+            created_at: None,
+            updated_at: None,
+        }
+    }
+
+    #[test]
+    fn absence_run_continues_across_month_boundary() {
+        let records = vec![record(2025, 1, &[29, 30, 31], &[]), record(2025, 2, &[3, 4], &[])];
+        let runs = absence_runs(&records, &HashMap::new(), date(2025, 12, 31), 3);
+        assert_eq!(runs, vec![vec![
+            date(2025, 1, 29), date(2025, 1, 30), date(2025, 1, 31), date(2025, 2, 3), date(2025, 2, 4),
+        ]]);
+    }
+
+    #[test]
+    fn month_without_record_breaks_absence_run() {
+        let records = vec![record(2025, 1, &[29, 30, 31], &[]), record(2025, 3, &[3, 4], &[])];
+        assert!(absence_runs(&records, &HashMap::new(), date(2025, 12, 31), 3).is_empty());
+    }
+
+    #[test]
+    fn holidays_do_not_break_absence_run() {
+        let records = vec![record(2025, 2, &[3, 5, 6], &[4])];
+        let holidays = HashMap::from([((2025, 2), HashSet::from([4]))]);
+        let runs = absence_runs(&records, &holidays, date(2025, 12, 31), 2);
+        assert_eq!(runs, vec![vec![date(2025, 2, 3), date(2025, 2, 5), date(2025, 2, 6)]]);
+    }
+
+    #[test]
+    fn unrecorded_working_day_breaks_absence_run() {
+        let records = vec![record(2025, 2, &[3, 5, 6], &[4])];
+        assert!(absence_runs(&records, &HashMap::new(), date(2025, 12, 31), 2).is_empty());
+    }
+
+    #[test]
+    fn runs_up_to_the_limit_are_not_flagged() {
+        let records = vec![record(2025, 2, &[3, 4, 5], &[]), record(2025, 3, &[3, 4, 5, 6], &[])];
+        let runs = absence_runs(&records, &HashMap::new(), date(2025, 12, 31), 3);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0][0], date(2025, 3, 3));
+    }
+
+    #[test]
+    fn in_service_from_the_month_of_appointment() {
+        let joined = date(2025, 3, 20);
+        assert!(!in_service(date(2025, 2, 1), date(2025, 12, 31), joined, None, None));
+        assert!(in_service(date(2025, 3, 1), date(2025, 12, 31), joined, None, None));
+    }
+
+    #[test]
+    fn in_service_until_the_month_of_leaving() {
+        let joined = date(2010, 1, 1);
+        let retired = Some(date(2025, 6, 15));
+        assert!(in_service(date(2025, 6, 1), date(2025, 12, 31), joined, retired, None));
+        assert!(!in_service(date(2025, 7, 1), date(2025, 12, 31), joined, retired, None));
+
+        let transferred = Some(date(2025, 8, 1));
+        assert!(in_service(date(2025, 8, 1), date(2025, 12, 31), joined, None, transferred));
+        assert!(!in_service(date(2025, 9, 1), date(2025, 12, 31), joined, None, transferred));
+    }
+
+    #[test]
+    fn not_in_service_for_future_months() {
+        let joined = date(2010, 1, 1);
+        assert!(in_service(date(2025, 6, 1), date(2025, 6, 1), joined, None, None));
+        assert!(!in_service(date(2025, 7, 1), date(2025, 6, 30), joined, None, None));
+    }
+}
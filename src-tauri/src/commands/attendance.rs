@@ -1,13 +1,21 @@
-use crate::models::attendance::*;
+use crate::models::{attendance::*, employee::Designation, holiday::WorkingDays, leave::LeaveApplication, dates::{days_in_month, format_date, parse_date}};
+use crate::commands::holiday::{holiday_dates_between, is_working_day, working_days_between, working_days_in_month};
+use crate::commands::settings::load_attendance_settings;
 use crate::database::{Collections, helpers::*};
 use crate::AppState;
-use mongodb::bson::{doc, oid::ObjectId, DateTime};
-use mongodb::options::{UpdateOptions};
+use mongodb::bson::{doc, oid::ObjectId, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
 use tauri::State;
 use futures::stream::TryStreamExt;
 use chrono::Datelike;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
 
-/// Get attendance records with optional filtering
+/// Get attendance records with optional filtering.
+/// A status filter returns the records with at least one day in that status, trimmed to
+/// those days when `matchingDaysOnly` is set. Designation and department are matched on
+/// the employee, the department being the employee's ministry.
 #[tauri::command]
 pub async fn get_attendance_records(
     state: State<'_, AppState>,
@@ -17,6 +25,8 @@ pub async fn get_attendance_records(
     let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
 
     let mut filter_doc = doc! {};
+    let mut employee_match = doc! {};
+    let mut day_filter = None;
 
     if let Some(f) = filter {
         if let Some(emp_num) = f.employee_number {
@@ -28,9 +38,37 @@ pub async fn get_attendance_records(
         if let Some(year) = f.year {
             filter_doc.insert("year", year);
         }
+        if let Some(status) = f.status {
+            let status = mongodb::bson::to_bson(&status)
+                .map_err(|e| format!("Failed to serialize attendance status: {}", e))?;
+            filter_doc.insert("records.status", status.clone());
+            if f.matching_days_only {
+                day_filter = Some(status);
+            }
+        }
+        if let Some(designation) = f.designation {
+            employee_match.insert("employee.designation", designation);
+        }
+        if let Some(department) = f.department {
+            employee_match.insert("employee.ministry", doc! { "$regex": escape_regex(&department), "$options": "i" });
+        }
+    }
+
+    let mut pipeline = vec![doc! { "$match": filter_doc }];
+    if !employee_match.is_empty() {
+        pipeline.push(employee_lookup_stage());
+        pipeline.push(doc! { "$match": employee_match });
+        pipeline.push(doc! { "$project": { "employee": 0 } });
+    }
+    if let Some(status) = day_filter {
+        pipeline.push(doc! {
+            "$addFields": {
+                "records": { "$filter": { "input": "$records", "cond": { "$eq": ["$$this.status", status] } } }
+            }
+        });
     }
 
-    let mut cursor = collection.find(filter_doc, None)
+    let mut cursor = collection.aggregate(pipeline, None)
         .await
         .map_err(|e| format!("Failed to find attendance records: {}", e))?;
 
@@ -38,6 +76,8 @@ pub async fn get_attendance_records(
     while let Some(record) = cursor.try_next()
         .await
         .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        let record: AttendanceRecord = mongodb::bson::from_document(record)
+            .map_err(|e| format!("Failed to read attendance record: {}", e))?;
         records.push(record);
     }
 
@@ -53,6 +93,16 @@ pub async fn create_attendance_record(
     let db = &state.db;
     let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
 
+    let employee_number = match find_employee_number(db, &request.employee_id).await? {
+        Some(number) => number,
+        None => return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("Employee not found".to_string()),
+        }),
+    };
+
     // Check if record already exists for this employee and month/year
     let existing = collection.find_one(
         doc! {
@@ -73,13 +123,35 @@ pub async fn create_attendance_record(
         });
     }
 
+    if is_month_locked(db, request.month, request.year).await? {
+        return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(month_locked_error(request.month, request.year)),
+        });
+    }
+
+    let settings = load_attendance_settings(db).await?;
+    let mut records = request.records;
+    for daily in &mut records {
+        if let Err(e) = daily.apply_office_hours(&settings) {
+            return Ok(AttendanceResponse {
+                success: false,
+                data: None,
+                message: None,
+                error: Some(format!("Day {}: {}", daily.date, e)),
+            });
+        }
+    }
+
     let record = AttendanceRecord {
         id: Some(ObjectId::new()),
         employee_id: request.employee_id,
-        employee_number: "".to_string(), // This should be populated from employee lookup
+        employee_number,
         month: request.month,
         year: request.year,
-        records: request.records,
+        records,
         created_at: Some(DateTime::now()),
         updated_at: Some(DateTime::now()),
     };
@@ -100,42 +172,85 @@ pub async fn create_attendance_record(
     }
 }
 
-/// Update attendance record
+/// Update attendance record, replacing the entry for the given day
 #[tauri::command]
 pub async fn update_attendance_record(
     state: State<'_, AppState>,
     request: UpdateAttendanceRequest,
 ) -> Result<AttendanceResponse, String> {
     let db = &state.db;
-    let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
 
-    // Find the current month/year record
-    let filter = doc! {
-        "employeeId": &request.employee_id,
-        "month": chrono::Utc::now().month() as i32,
-        "year": chrono::Utc::now().year()
+    // Validate the day against the requested month
+    let days = match days_in_month(request.year, request.month as u32) {
+        Some(days) => days as i32,
+        None => return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Invalid month: {}/{}", request.month, request.year)),
+        }),
+    };
+
+    if request.date < 1 || request.date > days {
+        return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!(
+                "Day {} does not exist in {}/{}",
+                request.date, request.month, request.year
+            )),
+        });
+    }
+
+    if is_month_locked(db, request.month, request.year).await? {
+        return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(month_locked_error(request.month, request.year)),
+        });
+    }
+
+    let employee_number = match find_employee_number(db, &request.employee_id).await? {
+        Some(number) => number,
+        None => return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("Employee not found".to_string()),
+        }),
     };
 
-    let daily_record = DailyAttendance {
+    let mut daily_record = DailyAttendance {
         date: request.date,
         status: request.status,
         notes: request.notes,
+        leave_application_id: None,
+        previous_status: None,
+        check_in: request.check_in,
+        check_out: request.check_out,
+        is_late: false,
+        is_early_departure: false,
+        short_leave: request.short_leave,
     };
 
-    // Update or insert the daily record
-    let update_doc = doc! {
-        "$set": {
-            "updatedAt": DateTime::now()
-        },
-        "$addToSet": {
-            "records": mongodb::bson::to_bson(&daily_record)
-                .map_err(|e| format!("Failed to serialize daily record: {}", e))?
-        }
-    };
+    let settings = load_attendance_settings(db).await?;
+    if let Err(e) = daily_record.apply_office_hours(&settings) {
+        return Ok(AttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(e),
+        });
+    }
 
-    let options = UpdateOptions::builder().upsert(true).build();
+    let result = modify_daily_entries(db, &request.employee_id, &employee_number, request.month, request.year, |records| {
+        records.retain(|r| r.date != daily_record.date);
+        records.push(daily_record);
+    }).await;
 
-    match collection.update_one(filter, update_doc, options).await {
+    match result {
         Ok(_) => Ok(AttendanceResponse {
             success: true,
             data: None,
@@ -151,6 +266,198 @@ pub async fn update_attendance_record(
     }
 }
 
+/// Mark attendance for the whole roster on one date in a single bulk write.
+/// Employees not listed in `entries` are marked present unless their day is already recorded.
+#[tauri::command]
+pub async fn mark_attendance_bulk(
+    state: State<'_, AppState>,
+    date: String,
+    entries: Vec<BulkAttendanceEntry>,
+) -> Result<BulkAttendanceResponse, String> {
+    let db = &state.db;
+    let employee_collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    let date = match parse_date(&date) {
+        Some(date) => date,
+        None => return Ok(BulkAttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("Invalid date. Use dd-MM-yyyy".to_string()),
+        }),
+    };
+    let (day, month, year) = (date.day() as i32, date.month() as i32, date.year());
+
+    if is_month_locked(db, month, year).await? {
+        return Ok(BulkAttendanceResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(month_locked_error(month, year)),
+        });
+    }
+
+    // Load the roster of unarchived staff who are still in service
+    let find_options = FindOptions::builder()
+        .projection(doc! { "employeeNumber": 1 })
+        .sort(doc! { "employeeNumber": 1 })
+        .build();
+
+    let roster_filter = doc! {
+        "archived": { "$ne": true },
+        "status": { "$in": ["Active", "On Leave", "Interdicted"] },
+    };
+
+    let mut cursor = employee_collection.find(roster_filter, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut roster: Vec<(String, String)> = Vec::new();
+    while let Some(employee) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        if let Ok(id) = employee.get_object_id("_id") {
+            let number = employee.get_str("employeeNumber").unwrap_or_default().to_string();
+            roster.push((object_id_to_string(&id), number));
+        }
+    }
+
+    let mut results = Vec::new();
+
+    // Listed employees that are not on the roster cannot be marked
+    for entry in &entries {
+        if !roster.iter().any(|(id, _)| *id == entry.employee_id) {
+            results.push(BulkAttendanceResult {
+                employee_id: entry.employee_id.clone(),
+                employee_number: String::new(),
+                status: entry.status,
+                success: false,
+                error: Some("Employee not found".to_string()),
+            });
+        }
+    }
+
+    let settings = load_attendance_settings(db).await?;
+    let holidays = holiday_dates_between(db, date, date).await?;
+    let working_day = is_working_day(date, &holidays);
+    let now = DateTime::now();
+    let mut updates = Vec::new();
+    let mut pending = Vec::new();
+
+    for (employee_id, employee_number) in roster {
+        let listed = entries.iter().find(|e| e.employee_id == employee_id);
+
+        // Nobody is presumed present on a weekend or holiday, only the listed staff are marked
+        if listed.is_none() && !working_day {
+            continue;
+        }
+
+        let mut daily = DailyAttendance {
+            date: day,
+            status: listed.map(|e| e.status).unwrap_or(AttendanceStatus::Present),
+            notes: listed.and_then(|e| e.notes.clone()),
+            leave_application_id: None,
+            previous_status: None,
+            check_in: listed.and_then(|e| e.check_in.clone()),
+            check_out: listed.and_then(|e| e.check_out.clone()),
+            is_late: false,
+            is_early_departure: false,
+            short_leave: listed.map(|e| e.short_leave).unwrap_or(false),
+        };
+
+        if let Err(e) = daily.apply_office_hours(&settings) {
+            results.push(BulkAttendanceResult {
+                employee_id,
+                employee_number,
+                status: daily.status,
+                success: false,
+                error: Some(e),
+            });
+            continue;
+        }
+        let daily_bson = mongodb::bson::to_bson(&daily)
+            .map_err(|e| format!("Failed to serialize daily record: {}", e))?;
+
+        let existing_records = doc! { "$ifNull": ["$records", []] };
+        let records_expr = if listed.is_some() {
+            // Replace the day entry
+            doc! {
+                "$concatArrays": [
+                    { "$filter": { "input": existing_records, "cond": { "$ne": ["$$this.date", day] } } },
+                    [{ "$literal": daily_bson }]
+                ]
+            }
+        } else {
+            // Default to present only when the day has not been recorded
+            doc! {
+                "$cond": [
+                    { "$in": [day, { "$ifNull": ["$records.date", []] }] },
+                    "$records",
+                    { "$concatArrays": [existing_records, [{ "$literal": daily_bson }]] }
+                ]
+            }
+        };
+
+        updates.push(doc! {
+            "q": { "employeeId": &employee_id, "month": month, "year": year },
+            "u": [{
+                "$set": {
+                    "employeeNumber": &employee_number,
+                    "records": records_expr,
+                    "createdAt": { "$ifNull": ["$createdAt", now] },
+                    "updatedAt": now
+                }
+            }],
+            "upsert": true
+        });
+        pending.push(BulkAttendanceResult {
+            employee_id,
+            employee_number,
+            status: daily.status,
+            success: true,
+            error: None,
+        });
+    }
+
+    if !updates.is_empty() {
+        let command = doc! {
+            "update": Collections::ATTENDANCE,
+            "updates": updates,
+            "ordered": false
+        };
+
+        let response = db.run_command(command, None)
+            .await
+            .map_err(|e| format!("Failed to mark attendance: {}", e))?;
+
+        // Unordered writes report failures per statement index
+        if let Ok(write_errors) = response.get_array("writeErrors") {
+            for error in write_errors.iter().filter_map(|e| e.as_document()) {
+                let index = error.get_i32("index").unwrap_or(-1);
+                if let Some(result) = usize::try_from(index).ok().and_then(|i| pending.get_mut(i)) {
+                    result.success = false;
+                    result.error = Some(error.get_str("errmsg").unwrap_or("Write failed").to_string());
+                }
+            }
+        }
+    }
+
+    results.extend(pending);
+    let failed = results.iter().filter(|r| !r.success).count();
+
+    Ok(BulkAttendanceResponse {
+        success: failed == 0,
+        message: Some(format!(
+            "Marked attendance for {} employees on {}, {} failed",
+            results.len() - failed,
+            format_date(&date),
+            failed
+        )),
+        data: Some(results),
+        error: None,
+    })
+}
+
 /// Delete attendance record
 #[tauri::command]
 pub async fn delete_attendance_record(
@@ -163,6 +470,21 @@ pub async fn delete_attendance_record(
     let filter = id_filter(&id)
         .map_err(|e| format!("Invalid record ID: {}", e))?;
 
+    let existing = collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find attendance record: {}", e))?;
+
+    if let Some(record) = existing {
+        if is_month_locked(db, record.month, record.year).await? {
+            return Ok(AttendanceResponse {
+                success: false,
+                data: None,
+                message: None,
+                error: Some(month_locked_error(record.month, record.year)),
+            });
+        }
+    }
+
     match collection.delete_one(filter, None).await {
         Ok(result) => {
             if result.deleted_count == 0 {
@@ -190,81 +512,183 @@ pub async fn delete_attendance_record(
     }
 }
 
-/// Get monthly summary
+/// Get monthly summary, optionally limited to a designation and ministry
 #[tauri::command]
 pub async fn get_monthly_summary(
     state: State<'_, AppState>,
     month: i32,
     year: i32,
+    designation: Option<Designation>,
+    ministry: Option<String>,
 ) -> Result<MonthlySummaryResponse, String> {
     let db = &state.db;
     let attendance_collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
-    let employee_collection = db.collection::<crate::models::employee::Employee>(Collections::EMPLOYEES);
 
-    // Get all attendance records for the specified month/year
-    let filter = doc! { "month": month, "year": year };
-    let mut cursor = attendance_collection.find(filter, None)
+    let total_working_days = working_days_in_month(db, month, year).await?.working_days;
+    let settings = load_attendance_settings(db).await?;
+
+    let mut employee_match = doc! {};
+    if let Some(designation) = designation {
+        employee_match.insert(
+            "employee.designation",
+            mongodb::bson::to_bson(&designation)
+                .map_err(|e| format!("Failed to serialize designation: {}", e))?,
+        );
+    }
+    if let Some(ministry) = ministry {
+        employee_match.insert("employee.ministry", doc! { "$regex": escape_regex(&ministry), "$options": "i" });
+    }
+
+    // Count the days matching a condition on each daily entry
+    let count_days = |cond: Document| doc! {
+        "$size": { "$filter": { "input": { "$ifNull": ["$records", []] }, "cond": cond } }
+    };
+
+    let pipeline = vec![
+        doc! { "$match": { "month": month, "year": year } },
+        employee_lookup_stage(),
+        doc! { "$unwind": "$employee" },
+        doc! { "$match": employee_match },
+        doc! {
+            "$project": {
+                "_id": 0,
+                "employeeId": 1,
+                "employeeNumber": "$employee.employeeNumber",
+                "fullName": "$employee.fullName",
+                "statusCounts": {
+                    "$arrayToObject": {
+                        "$map": {
+                            "input": { "$setUnion": [{ "$ifNull": ["$records.status", []] }] },
+                            "as": "status",
+                            "in": {
+                                "k": "$$status",
+                                "v": count_days(doc! { "$eq": ["$$this.status", "$$status"] })
+                            }
+                        }
+                    }
+                },
+                "lateArrivals": count_days(doc! { "$eq": ["$$this.isLate", true] }),
+                "earlyDepartures": count_days(doc! { "$eq": ["$$this.isEarlyDeparture", true] }),
+                "shortLeavesTaken": count_days(doc! { "$eq": ["$$this.shortLeave", true] })
+            }
+        },
+        doc! { "$sort": { "employeeNumber": 1 } },
+    ];
+
+    let mut cursor = attendance_collection.aggregate(pipeline, None)
         .await
-        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+        .map_err(|e| format!("Failed to summarise attendance records: {}", e))?;
 
     let mut summaries = Vec::new();
 
-    while let Some(record) = cursor.try_next()
+    while let Some(row) = cursor.try_next()
         .await
-        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        .map_err(|e| format!("Failed to iterate attendance summaries: {}", e))? {
+        let row: SummaryRow = mongodb::bson::from_document(row)
+            .map_err(|e| format!("Failed to read attendance summary: {}", e))?;
 
-        // Get employee details
-        let emp_filter = id_filter(&record.employee_id)
-            .map_err(|e| format!("Invalid employee ID: {}", e))?;
+        let mut status_counts: BTreeMap<AttendanceStatus, i32> = AttendanceStatus::ALL.iter()
+            .map(|status| (*status, 0))
+            .collect();
+        status_counts.extend(row.status_counts);
 
-        let employee = employee_collection.find_one(emp_filter, None)
-            .await
-            .map_err(|e| format!("Failed to find employee: {}", e))?;
-
-        if let Some(emp) = employee {
-            // Calculate statistics
-            let total_working_days = chrono::NaiveDate::from_ymd_opt(year, month as u32, 1)
-                .unwrap()
-                .with_month(month as u32 + 1)
-                .unwrap_or_else(|| chrono::NaiveDate::from_ymd_opt(year + 1, 1, 1).unwrap())
-                .pred_opt()
-                .unwrap()
-                .day() as i32;
-
-            let mut total_present = 0;
-            let mut total_absent = 0;
-            let mut total_half_days = 0;
-
-            for daily in &record.records {
-                match daily.status {
-                    AttendanceStatus::Present => total_present += 1,
-                    AttendanceStatus::Absent => total_absent += 1,
-                    AttendanceStatus::HalfDay => total_half_days += 1,
-                }
-            }
+        let totals = AttendanceTotals::from_counts(
+            &status_counts,
+            row.short_leaves_taken,
+            total_working_days,
+            settings.short_leaves_per_month,
+        );
+        let short_leave_excess = AttendanceTotals::short_leave_excess(
+            row.short_leaves_taken,
+            settings.short_leaves_per_month,
+        );
 
-            let attendance_percentage = if total_working_days > 0 {
-                ((total_present as f64 + (total_half_days as f64 * 0.5)) / total_working_days as f64) * 100.0
-            } else {
-                0.0
-            };
+        summaries.push(MonthlyAttendanceSummary {
+            employee_id: row.employee_id,
+            employee_number: row.employee_number,
+            full_name: row.full_name,
+            month,
+            year,
+            total_working_days,
+            status_counts,
+            total_attended_days: totals.attended_days,
+            total_leave_days: totals.leave_days,
+            late_arrivals: row.late_arrivals,
+            early_departures: row.early_departures,
+            short_leaves_taken: row.short_leaves_taken,
+            short_leave_excess,
+            attendance_percentage: totals.attendance_percentage,
+        });
+    }
 
-            summaries.push(MonthlyAttendanceSummary {
-                employee_id: record.employee_id,
-                employee_number: emp.employee_number,
-                full_name: emp.full_name,
-                month,
-                year,
-                total_working_days,
-                total_present,
-                total_absent,
-                total_half_days,
-                attendance_percentage,
+    Ok(MonthlySummaryResponse {
+        success: true,
+        data: Some(summaries),
+        message: None,
+        error: None,
+    })
+}
+
+/// Get each employee's attendance for a year, month by month
+#[tauri::command]
+pub async fn get_yearly_summary(
+    state: State<'_, AppState>,
+    year: i32,
+) -> Result<YearlySummaryResponse, String> {
+    let db = &state.db;
+    let attendance_collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+    let settings = load_attendance_settings(db).await?;
+
+    let pipeline = vec![
+        doc! { "$match": { "year": year } },
+        employee_lookup_stage(),
+        doc! { "$unwind": "$employee" },
+        doc! { "$sort": { "employee.employeeNumber": 1, "employeeId": 1, "month": 1 } },
+    ];
+
+    let mut cursor = attendance_collection.aggregate(pipeline, None)
+        .await
+        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+
+    let mut working_days_cache = HashMap::new();
+    let mut summaries: Vec<EmployeeAttendanceHistory> = Vec::new();
+
+    while let Some(document) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        let employee = document.get_document("employee").cloned().unwrap_or_default();
+        let record: AttendanceRecord = mongodb::bson::from_document(document)
+            .map_err(|e| format!("Failed to read attendance record: {}", e))?;
+
+        let working_days = cached_working_days(db, &mut working_days_cache, record.month, year).await?;
+        let totals = AttendanceTotals::from_days(
+            &record.records,
+            working_days.working_days,
+            settings.short_leaves_per_month,
+        );
+
+        let is_new_employee = summaries.last()
+            .map(|summary| summary.employee_id != record.employee_id)
+            .unwrap_or(true);
+        if is_new_employee {
+            summaries.push(EmployeeAttendanceHistory {
+                employee_id: record.employee_id.clone(),
+                employee_number: employee.get_str("employeeNumber").unwrap_or_default().to_string(),
+                full_name: employee.get_str("fullName").unwrap_or_default().to_string(),
+                from: format!("01-01-{}", year),
+                to: format!("31-12-{}", year),
+                months: Vec::new(),
+                total: AttendanceTotals::default(),
             });
         }
+
+        if let Some(summary) = summaries.last_mut() {
+            summary.total.add(&totals);
+            summary.months.push(MonthAttendanceTotals { month: record.month, year, totals });
+        }
     }
 
-    Ok(MonthlySummaryResponse {
+    Ok(YearlySummaryResponse {
         success: true,
         data: Some(summaries),
         message: None,
@@ -272,6 +696,99 @@ pub async fn get_monthly_summary(
     })
 }
 
+/// Get an employee's attendance between two dates (dd-MM-yyyy), month by month
+#[tauri::command]
+pub async fn get_employee_attendance_history(
+    state: State<'_, AppState>,
+    employee_id: String,
+    from: String,
+    to: String,
+) -> Result<AttendanceHistoryResponse, String> {
+    let db = &state.db;
+    let attendance_collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+    let employee_collection = db.collection::<crate::models::employee::Employee>(Collections::EMPLOYEES);
+
+    let history_error = |error: &str| AttendanceHistoryResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error.to_string()),
+    };
+
+    let (start, end) = match (parse_date(&from), parse_date(&to)) {
+        (Some(start), Some(end)) if start <= end => (start, end),
+        _ => return Ok(history_error("Invalid period. Use dd-MM-yyyy and an end date on or after the start date")),
+    };
+
+    let emp_filter = id_filter(&employee_id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let employee = match employee_collection.find_one(emp_filter, None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(history_error("Employee not found")),
+    };
+
+    let settings = load_attendance_settings(db).await?;
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "year": 1, "month": 1 })
+        .build();
+
+    let mut cursor = attendance_collection.find(
+        doc! { "employeeId": &employee_id, "year": { "$gte": start.year(), "$lte": end.year() } },
+        find_options
+    ).await
+        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+
+    let mut working_days_cache = HashMap::new();
+    let mut months = Vec::new();
+    let mut total = AttendanceTotals::default();
+
+    while let Some(record) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        let period = (record.year, record.month as u32);
+        if period < (start.year(), start.month()) || period > (end.year(), end.month()) {
+            continue;
+        }
+
+        // Only count the part of the first and last month inside the period
+        let working_days = cached_working_days(db, &mut working_days_cache, record.month, record.year).await?;
+        let first_day = if period == (start.year(), start.month()) { start.day() as i32 } else { 1 };
+        let last_day = if period == (end.year(), end.month()) {
+            end.day() as i32
+        } else {
+            working_days.calendar_days
+        };
+
+        let totals = AttendanceTotals::from_days(
+            record.records.iter().filter(|d| d.date >= first_day && d.date <= last_day),
+            working_days_between(&working_days, first_day, last_day),
+            settings.short_leaves_per_month,
+        );
+
+        total.add(&totals);
+        months.push(MonthAttendanceTotals { month: record.month, year: record.year, totals });
+    }
+
+    Ok(AttendanceHistoryResponse {
+        success: true,
+        data: Some(EmployeeAttendanceHistory {
+            employee_id,
+            employee_number: employee.employee_number,
+            full_name: employee.full_name,
+            from: format_date(&start),
+            to: format_date(&end),
+            months,
+            total,
+        }),
+        message: None,
+        error: None,
+    })
+}
+
 /// Backup monthly data
 #[tauri::command]
 pub async fn backup_monthly_data(
@@ -327,6 +844,15 @@ pub async fn clear_monthly_data(
     let db = &state.db;
     let attendance_collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
 
+    if is_month_locked(db, month, year).await? {
+        return Ok(crate::commands::print::PrintResponse {
+            success: false,
+            file_path: None,
+            message: None,
+            error: Some(month_locked_error(month, year)),
+        });
+    }
+
     // Delete attendance records
     let attendance_filter = doc! { "month": month, "year": year };
     let attendance_result = attendance_collection.delete_many(attendance_filter, None)
@@ -344,4 +870,456 @@ pub async fn clear_monthly_data(
         )),
         error: None,
     })
-}
\ No newline at end of file
+}
+
+/// Lock a month so its attendance can no longer be changed
+#[tauri::command]
+pub async fn lock_month(
+    state: State<'_, AppState>,
+    month: i32,
+    year: i32,
+    user: String,
+    reason: String,
+) -> Result<AttendanceLockResponse, String> {
+    set_month_lock(&state.db, month, year, true, user, reason).await
+}
+
+/// Unlock a previously locked month
+#[tauri::command]
+pub async fn unlock_month(
+    state: State<'_, AppState>,
+    month: i32,
+    year: i32,
+    user: String,
+    reason: String,
+) -> Result<AttendanceLockResponse, String> {
+    set_month_lock(&state.db, month, year, false, user, reason).await
+}
+
+/// Get month locks, optionally for one year
+#[tauri::command]
+pub async fn get_attendance_locks(
+    state: State<'_, AppState>,
+    year: Option<i32>,
+) -> Result<Vec<AttendanceLock>, String> {
+    let collection = state.db.collection::<AttendanceLock>(Collections::ATTENDANCE_LOCKS);
+
+    let mut filter_doc = doc! {};
+    if let Some(year) = year {
+        filter_doc.insert("year", year);
+    }
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "year": -1, "month": -1 })
+        .build();
+
+    let mut cursor = collection.find(filter_doc, find_options)
+        .await
+        .map_err(|e| format!("Failed to find attendance locks: {}", e))?;
+
+    let mut locks = Vec::new();
+    while let Some(lock) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance locks: {}", e))? {
+        locks.push(lock);
+    }
+
+    Ok(locks)
+}
+
+/// Back-fill and re-sync the employee numbers stored on attendance records and leave applications
+#[tauri::command]
+pub async fn resync_employee_numbers(
+    state: State<'_, AppState>,
+) -> Result<EmployeeNumberSyncResponse, String> {
+    let db = &state.db;
+    let employee_collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    let find_options = FindOptions::builder()
+        .projection(doc! { "employeeNumber": 1 })
+        .build();
+
+    let mut cursor = employee_collection.find(doc! {}, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut employees = Vec::new();
+    while let Some(employee) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        if let Ok(id) = employee.get_object_id("_id") {
+            let number = employee.get_str("employeeNumber").unwrap_or_default().to_string();
+            employees.push((object_id_to_string(&id), number));
+        }
+    }
+
+    let mut attendance_updated = 0;
+    let mut leave_applications_updated = 0;
+    for (employee_id, employee_number) in employees {
+        let (attendance, leaves) = sync_employee_number(db, &employee_id, &employee_number).await?;
+        attendance_updated += attendance;
+        leave_applications_updated += leaves;
+    }
+
+    Ok(EmployeeNumberSyncResponse {
+        success: true,
+        attendance_updated,
+        leave_applications_updated,
+        message: Some(format!(
+            "Updated {} attendance records and {} leave applications",
+            attendance_updated, leave_applications_updated
+        )),
+        error: None,
+    })
+}
+
+// Helper functions for attendance records
+
+/// Per-employee counts produced by the monthly summary pipeline
+#[derive(Deserialize)]
+struct SummaryRow {
+    #[serde(rename = "employeeId")]
+    employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    employee_number: String,
+    #[serde(rename = "fullName")]
+    full_name: String,
+    #[serde(rename = "statusCounts")]
+    status_counts: BTreeMap<AttendanceStatus, i32>,
+    #[serde(rename = "lateArrivals")]
+    late_arrivals: i32,
+    #[serde(rename = "earlyDepartures")]
+    early_departures: i32,
+    #[serde(rename = "shortLeavesTaken")]
+    short_leaves_taken: i32,
+}
+
+/// Join each attendance record to its employee as `employee`. Records store the
+/// employee id as a string, so it is converted before matching on `_id`.
+pub(crate) fn employee_lookup_stage() -> Document {
+    doc! {
+        "$lookup": {
+            "from": Collections::EMPLOYEES,
+            "let": { "employeeId": "$employeeId" },
+            "pipeline": [
+                {
+                    "$match": {
+                        "$expr": {
+                            "$eq": [
+                                "$_id",
+                                { "$convert": { "input": "$$employeeId", "to": "objectId", "onError": null, "onNull": null } }
+                            ]
+                        }
+                    }
+                },
+                { "$project": { "employeeNumber": 1, "fullName": 1, "designation": 1, "ministry": 1 } }
+            ],
+            "as": "employee"
+        }
+    }
+}
+
+/// Apply a change to the daily entries of an employee's month, creating the record if needed
+pub(crate) async fn modify_daily_entries<F>(
+    db: &Database,
+    employee_id: &str,
+    employee_number: &str,
+    month: i32,
+    year: i32,
+    modify: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut Vec<DailyAttendance>),
+{
+    let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+
+    let filter = doc! { "employeeId": employee_id, "month": month, "year": year };
+
+    let existing = collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find attendance record: {}", e))?;
+
+    match existing {
+        Some(record) => {
+            let mut records = record.records;
+            modify(&mut records);
+            records.sort_by_key(|r| r.date);
+
+            let mut set_doc = doc! {
+                "records": mongodb::bson::to_bson(&records)
+                    .map_err(|e| format!("Failed to serialize daily records: {}", e))?,
+                "updatedAt": DateTime::now()
+            };
+            if !employee_number.is_empty() {
+                set_doc.insert("employeeNumber", employee_number);
+            }
+            let update_doc = doc! { "$set": set_doc };
+
+            collection.update_one(filter, update_doc, None)
+                .await
+                .map_err(|e| format!("Failed to update attendance record: {}", e))?;
+        },
+        None => {
+            let mut records = Vec::new();
+            modify(&mut records);
+            if records.is_empty() {
+                return Ok(());
+            }
+            records.sort_by_key(|r| r.date);
+
+            let record = AttendanceRecord {
+                id: Some(ObjectId::new()),
+                employee_id: employee_id.to_string(),
+                employee_number: employee_number.to_string(),
+                month,
+                year,
+                records,
+                created_at: Some(DateTime::now()),
+                updated_at: Some(DateTime::now()),
+            };
+
+            collection.insert_one(&record, None)
+                .await
+                .map_err(|e| format!("Failed to create attendance record: {}", e))?;
+        },
+    }
+
+    Ok(())
+}
+
+/// Mark the days covered by an approved leave application as leave
+pub(crate) async fn apply_leave_to_attendance(
+    db: &Database,
+    application: &LeaveApplication,
+) -> Result<(), String> {
+    let (start, end) = match application.date_range() {
+        Some(range) => range,
+        None => return Ok(()),
+    };
+    let application_id = match application.id.as_ref() {
+        Some(id) => object_id_to_string(id),
+        None => return Ok(()),
+    };
+    let status = if application.is_half_day {
+        AttendanceStatus::HalfDayLeave
+    } else {
+        AttendanceStatus::from_leave_type(application.leave_type)
+    };
+
+    // Group the leave days by month. Weekends and holidays keep their own status.
+    let holidays = holiday_dates_between(db, start, end).await?;
+    let mut months: Vec<(i32, i32, Vec<i32>)> = Vec::new();
+    for date in application.working_days(|d| is_working_day(d, &holidays)) {
+        let (year, month, day) = (date.year(), date.month() as i32, date.day() as i32);
+        match months.last_mut() {
+            Some((y, m, days)) if *y == year && *m == month => days.push(day),
+            _ => months.push((year, month, vec![day])),
+        }
+    }
+
+    for (year, month, days) in months {
+        modify_daily_entries(db, &application.employee_id, &application.employee_number, month, year, |records| {
+            for day in days {
+                match records.iter_mut().find(|r| r.date == day) {
+                    Some(entry) => {
+                        if entry.leave_application_id.is_none() {
+                            entry.previous_status = Some(entry.status);
+                        }
+                        entry.status = status;
+                        entry.leave_application_id = Some(application_id.clone());
+                    },
+                    None => records.push(DailyAttendance {
+                        date: day,
+                        status,
+                        notes: None,
+                        leave_application_id: Some(application_id.clone()),
+                        previous_status: None,
+                        check_in: None,
+                        check_out: None,
+                        is_late: false,
+                        is_early_departure: false,
+                        short_leave: false,
+                    }),
+                }
+            }
+        }).await?;
+    }
+
+    Ok(())
+}
+
+/// Restore the days marked by a leave application that has been rejected or cancelled
+pub(crate) async fn revert_leave_from_attendance(
+    db: &Database,
+    application_id: &str,
+) -> Result<(), String> {
+    let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+
+    let mut cursor = collection.find(doc! { "records.leaveApplicationId": application_id }, None)
+        .await
+        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+
+    let mut affected = Vec::new();
+    while let Some(record) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        affected.push(record);
+    }
+
+    for record in affected {
+        modify_daily_entries(db, &record.employee_id, &record.employee_number, record.month, record.year, |records| {
+            *records = std::mem::take(records)
+                .into_iter()
+                .filter_map(|mut entry| {
+                    if entry.leave_application_id.as_deref() != Some(application_id) {
+                        return Some(entry);
+                    }
+                    // Days that had no entry before the leave are removed again
+                    let previous = entry.previous_status.take()?;
+                    entry.status = previous;
+                    entry.leave_application_id = None;
+                    Some(entry)
+                })
+                .collect();
+        }).await?;
+    }
+
+    Ok(())
+}
+
+async fn set_month_lock(
+    db: &Database,
+    month: i32,
+    year: i32,
+    locked: bool,
+    user: String,
+    reason: String,
+) -> Result<AttendanceLockResponse, String> {
+    let collection = db.collection::<AttendanceLock>(Collections::ATTENDANCE_LOCKS);
+
+    let lock_error = |error: String| AttendanceLockResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error),
+    };
+
+    if days_in_month(year, month as u32).is_none() {
+        return Ok(lock_error(format!("Invalid month: {}/{}", month, year)));
+    }
+    if user.trim().is_empty() || reason.trim().is_empty() {
+        return Ok(lock_error("A user and a reason are required".to_string()));
+    }
+
+    let filter = doc! { "month": month, "year": year };
+    let currently_locked = is_month_locked(db, month, year).await?;
+    if currently_locked == locked {
+        let state = if locked { "already locked" } else { "not locked" };
+        return Ok(lock_error(format!("Attendance for {}/{} is {}", month, year, state)));
+    }
+
+    let event = AttendanceLockEvent {
+        locked,
+        user: user.trim().to_string(),
+        reason: reason.trim().to_string(),
+        at: DateTime::now(),
+    };
+    let update_doc = doc! {
+        "$set": { "locked": locked, "updatedAt": DateTime::now() },
+        "$push": {
+            "history": mongodb::bson::to_bson(&event)
+                .map_err(|e| format!("Failed to serialize lock event: {}", e))?
+        }
+    };
+    let options = mongodb::options::UpdateOptions::builder().upsert(true).build();
+
+    collection.update_one(filter.clone(), update_doc, options)
+        .await
+        .map_err(|e| format!("Failed to update attendance lock: {}", e))?;
+
+    let lock = collection.find_one(filter, None)
+        .await
+        .map_err(|e| format!("Failed to find attendance lock: {}", e))?;
+
+    Ok(AttendanceLockResponse {
+        success: true,
+        data: lock,
+        message: Some(format!(
+            "Attendance for {}/{} {}",
+            month, year, if locked { "locked" } else { "unlocked" }
+        )),
+        error: None,
+    })
+}
+
+/// Whether the attendance of a month has been locked
+pub(crate) async fn is_month_locked(db: &Database, month: i32, year: i32) -> Result<bool, String> {
+    let collection = db.collection::<AttendanceLock>(Collections::ATTENDANCE_LOCKS);
+
+    let lock = collection.find_one(doc! { "month": month, "year": year, "locked": true }, None)
+        .await
+        .map_err(|e| format!("Failed to check attendance lock: {}", e))?;
+
+    Ok(lock.is_some())
+}
+
+pub(crate) fn month_locked_error(month: i32, year: i32) -> String {
+    format!("Attendance for {}/{} is locked and cannot be changed", month, year)
+}
+
+/// Working days of a month, loaded once per month for roll-ups across many records
+pub(crate) async fn cached_working_days(
+    db: &Database,
+    cache: &mut HashMap<(i32, i32), WorkingDays>,
+    month: i32,
+    year: i32,
+) -> Result<WorkingDays, String> {
+    if let Some(working_days) = cache.get(&(year, month)) {
+        return Ok(working_days.clone());
+    }
+    let working_days = working_days_in_month(db, month, year).await?;
+    cache.insert((year, month), working_days.clone());
+    Ok(working_days)
+}
+
+/// Look up the employee number of an employee, or None if the employee does not exist
+async fn find_employee_number(db: &Database, employee_id: &str) -> Result<Option<String>, String> {
+    let collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    let filter = match id_filter(employee_id) {
+        Ok(filter) => filter,
+        Err(_) => return Ok(None),
+    };
+    let find_options = mongodb::options::FindOneOptions::builder()
+        .projection(doc! { "employeeNumber": 1 })
+        .build();
+
+    let employee = collection.find_one(filter, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))?;
+
+    Ok(employee.map(|e| e.get_str("employeeNumber").unwrap_or_default().to_string()))
+}
+
+/// Copy an employee's current number onto their attendance records and leave applications.
+/// Returns the number of attendance records and leave applications changed.
+pub(crate) async fn sync_employee_number(
+    db: &Database,
+    employee_id: &str,
+    employee_number: &str,
+) -> Result<(u64, u64), String> {
+    let filter = doc! { "employeeId": employee_id, "employeeNumber": { "$ne": employee_number } };
+    let update_doc = doc! { "$set": { "employeeNumber": employee_number } };
+
+    let attendance = db.collection::<Document>(Collections::ATTENDANCE)
+        .update_many(filter.clone(), update_doc.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to update attendance employee numbers: {}", e))?;
+
+    let leaves = db.collection::<Document>(Collections::LEAVES)
+        .update_many(filter, update_doc, None)
+        .await
+        .map_err(|e| format!("Failed to update leave employee numbers: {}", e))?;
+
+    Ok((attendance.modified_count, leaves.modified_count))
+}
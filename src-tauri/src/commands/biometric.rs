@@ -0,0 +1,411 @@
+use crate::models::{attendance::*, biometric::*, settings::{parse_time, AttendanceSettings, TIME_FORMAT}, dates::format_date};
+use crate::commands::attendance::{is_month_locked, modify_daily_entries, month_locked_error};
+use crate::commands::settings::load_attendance_settings;
+use crate::database::{Collections, helpers::*};
+use crate::AppState;
+use mongodb::bson::{doc, Document};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use tauri::State;
+use futures::stream::TryStreamExt;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+/// Timestamp layouts tried when the import options do not name one
+const TIMESTAMP_FORMATS: [&str; 8] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%d-%m-%Y %H:%M:%S",
+    "%d-%m-%Y %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+    "%d/%m/%Y %H:%M",
+    "%Y/%m/%d %H:%M:%S",
+    "%Y/%m/%d %H:%M",
+];
+
+/// A single clock punch read from the log
+struct Punch {
+    user_id: String,
+    timestamp: NaiveDateTime,
+    direction: Option<PunchDirection>,
+}
+
+/// Import a fingerprint terminal punch log into the attendance records.
+/// With `dryRun` set the derived days are returned without saving anything.
+#[tauri::command]
+pub async fn import_biometric_log(
+    state: State<'_, AppState>,
+    content: String,
+    options: PunchLogImportOptions,
+) -> Result<PunchLogImportResponse, String> {
+    let db = &state.db;
+
+    let settings = load_attendance_settings(db).await?;
+    if let Err(e) = settings.validate() {
+        return Ok(import_error(&format!("Invalid attendance settings: {}", e)));
+    }
+
+    let (punches, total_lines, mut errors) = match parse_punch_log(&content, &options) {
+        Ok(parsed) => parsed,
+        Err(e) => return Ok(import_error(&e)),
+    };
+
+    if punches.is_empty() {
+        return Ok(import_error("No punches found in the log file"));
+    }
+    let parsed_punches = punches.len();
+
+    let employees = load_employee_numbers(db).await?;
+
+    // Group the punches by device user and day, so "0042" and "42" are one employee
+    let mut grouped: BTreeMap<(String, NaiveDate), Vec<Punch>> = BTreeMap::new();
+    let mut unmatched = BTreeSet::new();
+    for punch in punches {
+        let user_id = normalize_user_id(&punch.user_id);
+        if !employees.contains_key(&user_id) {
+            unmatched.insert(punch.user_id);
+            continue;
+        }
+        grouped.entry((user_id, punch.timestamp.date()))
+            .or_default()
+            .push(punch);
+    }
+
+    // Derive the attendance of each day
+    let mut days: Vec<ImportedAttendanceDay> = Vec::new();
+    let mut months: BTreeMap<(String, i32, u32), Vec<(usize, u32)>> = BTreeMap::new();
+    for ((user_id, date), mut day_punches) in grouped {
+        let (employee_id, employee_number) = employees[&user_id].clone();
+        day_punches.sort_by_key(|p| p.timestamp);
+
+        let day = match derive_day(&day_punches, &settings) {
+            Ok(daily) => daily,
+            Err(e) => {
+                errors.push(format!("{} on {}: {}", user_id, format_date(&date), e));
+                continue;
+            }
+        };
+
+        months.entry((employee_id.clone(), date.year(), date.month()))
+            .or_default()
+            .push((days.len(), date.day()));
+
+        days.push(ImportedAttendanceDay {
+            employee_id,
+            employee_number,
+            device_user_id: user_id,
+            date: format_date(&date),
+            status: day.status,
+            check_in: day.check_in,
+            check_out: day.check_out,
+            is_late: day.is_late,
+            is_early_departure: day.is_early_departure,
+            punches: day_punches.len(),
+            kept_existing: false,
+        });
+    }
+
+    // Merge into the monthly records, leaving leave, holidays and official duty untouched
+    let collection = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+    let mut records_updated = 0;
+    let mut locked_months = BTreeSet::new();
+    for ((employee_id, year, month), entries) in months {
+        if is_month_locked(db, month as i32, year).await? {
+            locked_months.insert((year, month));
+            for (index, _) in &entries {
+                days[*index].kept_existing = true;
+            }
+            continue;
+        }
+
+        let employee_number = days[entries[0].0].employee_number.clone();
+        let mut merge = |records: &mut Vec<DailyAttendance>| {
+            for (index, day_of_month) in &entries {
+                days[*index].kept_existing = !merge_imported_day(records, *day_of_month as i32, &days[*index]);
+            }
+        };
+
+        if options.dry_run {
+            let existing = collection.find_one(
+                doc! { "employeeId": &employee_id, "month": month as i32, "year": year },
+                None
+            ).await
+                .map_err(|e| format!("Failed to find attendance record: {}", e))?;
+            let mut records = existing.map(|r| r.records).unwrap_or_default();
+            merge(&mut records);
+        } else {
+            modify_daily_entries(db, &employee_id, &employee_number, month as i32, year, merge).await?;
+            records_updated += 1;
+        }
+    }
+
+    for (year, month) in locked_months {
+        errors.push(month_locked_error(month as i32, year));
+    }
+
+    let message = if options.dry_run {
+        format!("Preview of {} days from {} punches", days.len(), parsed_punches)
+    } else {
+        format!("Imported {} days into {} attendance records", days.len(), records_updated)
+    };
+
+    Ok(PunchLogImportResponse {
+        success: true,
+        data: Some(PunchLogImportResult {
+            dry_run: options.dry_run,
+            total_lines,
+            parsed_punches,
+            days,
+            unmatched_user_ids: unmatched.into_iter().collect(),
+            errors,
+            records_updated,
+        }),
+        message: Some(message),
+        error: None,
+    })
+}
+
+fn import_error(error: &str) -> PunchLogImportResponse {
+    PunchLogImportResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error.to_string()),
+    }
+}
+
+/// Map normalised employee numbers of unarchived staff to (employee id, employee number)
+async fn load_employee_numbers(db: &Database) -> Result<HashMap<String, (String, String)>, String> {
+    let collection = db.collection::<Document>(Collections::EMPLOYEES);
+    let find_options = FindOptions::builder()
+        .projection(doc! { "employeeNumber": 1 })
+        .build();
+
+    let mut cursor = collection.find(doc! { "archived": { "$ne": true } }, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut employees = HashMap::new();
+    while let Some(employee) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        if let Ok(id) = employee.get_object_id("_id") {
+            let number = employee.get_str("employeeNumber").unwrap_or_default().to_string();
+            employees.insert(normalize_user_id(&number), (object_id_to_string(&id), number));
+        }
+    }
+
+    Ok(employees)
+}
+
+/// Terminals pad numeric user ids with zeros, so compare numeric ids by value
+fn normalize_user_id(value: &str) -> String {
+    let value = value.trim();
+    if !value.is_empty() && value.chars().all(|c| c.is_ascii_digit()) {
+        let trimmed = value.trim_start_matches('0');
+        if trimmed.is_empty() { "0".to_string() } else { trimmed.to_string() }
+    } else {
+        value.to_uppercase()
+    }
+}
+
+/// Parse the punch log. Returns the punches, the number of data lines and
+/// an error for every line that could not be read.
+fn parse_punch_log(
+    content: &str,
+    options: &PunchLogImportOptions,
+) -> Result<(Vec<Punch>, usize, Vec<String>), String> {
+    let delimiter = match options.delimiter.as_deref() {
+        Some("\\t") | Some("tab") => b'\t',
+        Some(d) if d.len() == 1 => d.as_bytes()[0],
+        Some(d) => return Err(format!("Invalid delimiter '{}'", d)),
+        None => match options.format {
+            PunchLogFormat::ZkTeco => b'\t',
+            PunchLogFormat::Csv => b',',
+        },
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(options.has_headers)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let headers: Vec<String> = if options.has_headers {
+        reader.headers()
+            .map_err(|e| format!("Failed to read the header row: {}", e))?
+            .iter()
+            .map(|h| h.to_lowercase())
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    // ZKTeco attlog: user id, timestamp, device, state (0 = in, 1 = out), verify mode, work code
+    let (user_col, timestamp_col, time_col, direction_col) = match (options.format, &options.columns) {
+        (_, Some(columns)) => (
+            resolve_column(&columns.user_id, &headers)?,
+            resolve_column(&columns.timestamp, &headers)?,
+            columns.time.as_deref().map(|c| resolve_column(c, &headers)).transpose()?,
+            columns.direction.as_deref().map(|c| resolve_column(c, &headers)).transpose()?,
+        ),
+        (PunchLogFormat::ZkTeco, None) => (0, 1, None, Some(3)),
+        (PunchLogFormat::Csv, None) => {
+            return Err("A column mapping is required for CSV punch logs".to_string());
+        }
+    };
+
+    let mut punches = Vec::new();
+    let mut errors = Vec::new();
+    let mut total_lines = 0;
+
+    for (index, row) in reader.records().enumerate() {
+        let line = index + if options.has_headers { 2 } else { 1 };
+        let row = match row {
+            Ok(row) => row,
+            Err(e) => {
+                errors.push(format!("Line {}: {}", line, e));
+                continue;
+            }
+        };
+        if row.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        total_lines += 1;
+
+        let user_id = row.get(user_col).unwrap_or_default().to_string();
+        if user_id.is_empty() {
+            errors.push(format!("Line {}: missing user id", line));
+            continue;
+        }
+
+        let mut timestamp_text = row.get(timestamp_col).unwrap_or_default().to_string();
+        if let Some(col) = time_col {
+            timestamp_text = format!("{} {}", timestamp_text, row.get(col).unwrap_or_default());
+        }
+        let timestamp = match parse_timestamp(&timestamp_text, options.timestamp_format.as_deref()) {
+            Some(timestamp) => timestamp,
+            None => {
+                errors.push(format!("Line {}: invalid timestamp '{}'", line, timestamp_text));
+                continue;
+            }
+        };
+
+        let direction = direction_col
+            .and_then(|col| row.get(col))
+            .and_then(parse_direction);
+
+        punches.push(Punch { user_id, timestamp, direction });
+    }
+
+    Ok((punches, total_lines, errors))
+}
+
+/// A header name (case-insensitive) or a zero-based column index
+fn resolve_column(column: &str, headers: &[String]) -> Result<usize, String> {
+    let column = column.trim();
+    if let Some(position) = headers.iter().position(|h| *h == column.to_lowercase()) {
+        return Ok(position);
+    }
+    column.parse::<usize>()
+        .map_err(|_| format!("Unknown column '{}'", column))
+}
+
+fn parse_timestamp(value: &str, format: Option<&str>) -> Option<NaiveDateTime> {
+    let value = value.trim();
+    match format {
+        Some(format) => NaiveDateTime::parse_from_str(value, format).ok(),
+        None => TIMESTAMP_FORMATS.iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok()),
+    }
+}
+
+fn parse_direction(value: &str) -> Option<PunchDirection> {
+    let value: String = value.to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect();
+    match value.as_str() {
+        // 4 and 5 are the overtime in/out states on ZKTeco terminals
+        "0" | "4" | "i" | "in" | "checkin" | "cin" | "otin" => Some(PunchDirection::In),
+        "1" | "5" | "o" | "out" | "checkout" | "cout" | "otout" => Some(PunchDirection::Out),
+        _ => None,
+    }
+}
+
+/// Derive a day's attendance from its punches, sorted by time. The arrival is the
+/// first in punch and the departure the last out punch, falling back to the first
+/// and last punch when the terminal does not record a direction.
+fn derive_day(punches: &[Punch], settings: &AttendanceSettings) -> Result<DailyAttendance, String> {
+    let first = punches.iter()
+        .find(|p| p.direction != Some(PunchDirection::Out))
+        .or_else(|| punches.first())
+        .map(|p| p.timestamp.time());
+    let last = punches.iter()
+        .rev()
+        .find(|p| p.direction != Some(PunchDirection::In))
+        .map(|p| p.timestamp.time())
+        .filter(|time| punches.len() > 1 && Some(*time) > first);
+
+    let format_time = |time: NaiveTime| time.format(TIME_FORMAT).to_string();
+
+    let mut daily = DailyAttendance {
+        date: 0,
+        status: AttendanceStatus::Present,
+        notes: None,
+        leave_application_id: None,
+        previous_status: None,
+        check_in: first.map(format_time),
+        check_out: last.map(format_time),
+        is_late: false,
+        is_early_departure: false,
+        short_leave: false,
+    };
+    daily.apply_office_hours(settings)?;
+
+    // Less than half of the office hours worked counts as a half day
+    if let (Some(arrival), Some(departure)) = (first, last) {
+        let office_start = parse_time(&settings.office_start)
+            .ok_or_else(|| "Invalid office start time in settings".to_string())?;
+        let office_end = parse_time(&settings.office_end)
+            .ok_or_else(|| "Invalid office end time in settings".to_string())?;
+        if (departure - arrival) * 2 < office_end - office_start {
+            daily.status = AttendanceStatus::HalfDay;
+        }
+    }
+
+    Ok(daily)
+}
+
+/// Apply an imported day to a month's entries. Days already holding leave, a holiday
+/// or official duty are kept as they are; returns whether the day was applied.
+fn merge_imported_day(records: &mut Vec<DailyAttendance>, day: i32, imported: &ImportedAttendanceDay) -> bool {
+    match records.iter_mut().find(|r| r.date == day) {
+        Some(existing) if !matches!(existing.status,
+            AttendanceStatus::Present | AttendanceStatus::Absent | AttendanceStatus::HalfDay) => false,
+        Some(existing) => {
+            existing.status = imported.status;
+            existing.check_in = imported.check_in.clone();
+            existing.check_out = imported.check_out.clone();
+            existing.is_late = imported.is_late;
+            existing.is_early_departure = imported.is_early_departure;
+            true
+        },
+        None => {
+            records.push(DailyAttendance {
+                date: day,
+                status: imported.status,
+                notes: None,
+                leave_application_id: None,
+                previous_status: None,
+                check_in: imported.check_in.clone(),
+                check_out: imported.check_out.clone(),
+                is_late: imported.is_late,
+                is_early_departure: imported.is_early_departure,
+                short_leave: false,
+            });
+            true
+        },
+    }
+}
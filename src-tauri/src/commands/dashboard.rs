@@ -0,0 +1,224 @@
+use crate::models::{dashboard::*, employee::{Employee, EmploymentStatus}, dates::{add_years, format_date}};
+use crate::commands::settings::load_retirement_settings;
+use crate::database::{Collections, helpers::*};
+use crate::AppState;
+use mongodb::bson::{doc, RawDocumentBuf};
+use mongodb::Database;
+use tauri::State;
+use chrono::{Datelike, Duration, NaiveDate};
+
+/// Years of probation after the first appointment before the service is confirmed
+const PROBATION_YEARS: i32 = 3;
+
+/// Years after the Grade III appointment by which the efficiency bar exam must be passed
+const EB_EXAM_DUE_YEARS: i32 = 3;
+
+/// Years after the first appointment by which the second language must be passed
+const SECOND_LANGUAGE_DUE_YEARS: i32 = 5;
+
+/// Get the HR deadlines falling within the next `window_days` days, along with
+/// confirmations and exams already overdue
+#[tauri::command]
+pub async fn get_upcoming_events(
+    state: State<'_, AppState>,
+    window_days: i64,
+) -> Result<UpcomingEventsResponse, String> {
+    if !(0..=366).contains(&window_days) {
+        return Ok(UpcomingEventsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("The window must be between 0 and 366 days".to_string()),
+        });
+    }
+
+    let upcoming_events = find_upcoming_events(&state.db, window_days).await?;
+
+    Ok(UpcomingEventsResponse {
+        success: true,
+        message: Some(format!("{} events in the next {} days", upcoming_events.events.len(), window_days)),
+        data: Some(upcoming_events),
+        error: None,
+    })
+}
+
+// Helper functions for upcoming events
+
+/// Work out the HR deadlines of the staff in service for the next `window_days` days
+pub(crate) async fn find_upcoming_events(db: &Database, window_days: i64) -> Result<UpcomingEvents, String> {
+    let collection = db.collection::<Employee>(Collections::EMPLOYEES);
+
+    let today = chrono::Local::now().date_naive();
+    let until = today + Duration::days(window_days);
+    let retirement_settings = load_retirement_settings(db).await?;
+
+    let mut cursor = collection.clone_with_type::<RawDocumentBuf>().find(doc! { "archived": { "$ne": true } }, None)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut events = Vec::new();
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        // Only staff still in service have deadlines
+        if !matches!(employee.status,
+            EmploymentStatus::Active | EmploymentStatus::OnLeave | EmploymentStatus::Interdicted) {
+            continue;
+        }
+        employee.derive_dates(&retirement_settings, today);
+
+        let mut event = |event_type: UpcomingEventType, date: NaiveDate, description: String| {
+            events.push(UpcomingEvent {
+                event_type,
+                employee_id: employee.id.as_ref().map(object_id_to_string).unwrap_or_default(),
+                employee_number: employee.employee_number.clone(),
+                full_name: employee.full_name.clone(),
+                designation: employee.designation,
+                date: format_date(&date),
+                days_until: date.signed_duration_since(today).num_days(),
+                description,
+            });
+        };
+        let upcoming = |date: NaiveDate| date >= today && date <= until;
+
+        if let Some(retired_date) = employee.retired_date.filter(|d| upcoming(*d)) {
+            event(
+                UpcomingEventType::Retirement,
+                retired_date,
+                format!("Retires at {}", retired_date.year() - employee.date_of_birth.year()),
+            );
+        }
+
+        let increment = parse_day_month(&employee.increment_date)
+            .and_then(|(day, month)| next_anniversary(day, month, today))
+            .filter(|d| upcoming(*d));
+        if let Some(increment) = increment {
+            event(UpcomingEventType::Increment, increment, "Annual increment due".to_string());
+        }
+
+        // Deadlines not yet met stay listed once they have passed
+        if !employee.service_confirmed {
+            let due = add_years(&employee.first_appointment_date, PROBATION_YEARS);
+            if due <= until {
+                event(UpcomingEventType::ServiceConfirmation, due, "Probation ends, service not confirmed".to_string());
+            }
+        }
+
+        if !employee.eb_pass {
+            let grade_iii = employee.grade_appointment_date.grade_iii
+                .unwrap_or(employee.first_appointment_date);
+            let due = add_years(&grade_iii, EB_EXAM_DUE_YEARS);
+            if due <= until {
+                event(UpcomingEventType::EbExam, due, "Efficiency bar exam not passed".to_string());
+            }
+        }
+
+        if !employee.second_language_passed {
+            let due = add_years(&employee.first_appointment_date, SECOND_LANGUAGE_DUE_YEARS);
+            if due <= until {
+                event(UpcomingEventType::SecondLanguage, due, "Second language proficiency not passed".to_string());
+            }
+        }
+
+        let birthday = next_anniversary(employee.date_of_birth.day(), employee.date_of_birth.month(), today)
+            .filter(|d| upcoming(*d));
+        if let Some(birthday) = birthday {
+            event(
+                UpcomingEventType::Birthday,
+                birthday,
+                format!("Turns {}", birthday.year() - employee.date_of_birth.year()),
+            );
+        }
+    }
+
+    events.sort_by(|a, b| {
+        (a.days_until, a.event_type, &a.employee_number)
+            .cmp(&(b.days_until, b.event_type, &b.employee_number))
+    });
+
+    let counts: Vec<UpcomingEventCount> = [
+        UpcomingEventType::Retirement,
+        UpcomingEventType::Increment,
+        UpcomingEventType::ServiceConfirmation,
+        UpcomingEventType::EbExam,
+        UpcomingEventType::SecondLanguage,
+        UpcomingEventType::Birthday,
+    ].iter().map(|event_type| {
+        let of_type = events.iter().filter(|e| e.event_type == *event_type);
+        UpcomingEventCount {
+            event_type: *event_type,
+            count: of_type.clone().count(),
+            overdue: of_type.filter(|e| e.days_until < 0).count(),
+        }
+    }).collect();
+
+    Ok(UpcomingEvents {
+        window_days,
+        from: format_date(&today),
+        to: format_date(&until),
+        events,
+        counts,
+    })
+}
+
+/// Parse a dd-MM day of the year, such as an increment date
+fn parse_day_month(value: &str) -> Option<(u32, u32)> {
+    let (day, month) = value.trim().split_once('-')?;
+    let (day, month) = (day.parse().ok()?, month.parse().ok()?);
+
+    // Reject days that fall in no year at all
+    NaiveDate::from_ymd_opt(2000, month, day)?;
+    Some((day, month))
+}
+
+/// The next date on or after `today` falling on the given day of the year.
+/// 29 February falls on the 28th in other years.
+fn next_anniversary(day: u32, month: u32, today: NaiveDate) -> Option<NaiveDate> {
+    let in_year = |year: i32| NaiveDate::from_ymd_opt(year, month, day)
+        .or_else(|| NaiveDate::from_ymd_opt(year, month, 28));
+
+    in_year(today.year())
+        .filter(|date| *date >= today)
+        .or_else(|| in_year(today.year() + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_day_and_month() {
+        assert_eq!(parse_day_month("05-03"), Some((5, 3)));
+        assert_eq!(parse_day_month(" 29-02 "), Some((29, 2)));
+    }
+
+    #[test]
+    fn rejects_invalid_day_and_month() {
+        assert_eq!(parse_day_month("31-02"), None);
+        assert_eq!(parse_day_month("01-13"), None);
+        assert_eq!(parse_day_month("0503"), None);
+        assert_eq!(parse_day_month(""), None);
+    }
+
+    #[test]
+    fn anniversary_later_this_year() {
+        assert_eq!(next_anniversary(5, 3, date(2025, 1, 10)), Some(date(2025, 3, 5)));
+        assert_eq!(next_anniversary(5, 3, date(2025, 3, 5)), Some(date(2025, 3, 5)));
+    }
+
+    #[test]
+    fn anniversary_passed_moves_to_next_year() {
+        assert_eq!(next_anniversary(5, 3, date(2025, 3, 6)), Some(date(2026, 3, 5)));
+    }
+
+    #[test]
+    fn leap_day_anniversary_falls_on_28_february_outside_leap_years() {
+        assert_eq!(next_anniversary(29, 2, date(2025, 1, 10)), Some(date(2025, 2, 28)));
+        assert_eq!(next_anniversary(29, 2, date(2027, 3, 1)), Some(date(2028, 2, 29)));
+        assert_eq!(next_anniversary(29, 2, date(2028, 2, 29)), Some(date(2028, 2, 29)));
+    }
+}
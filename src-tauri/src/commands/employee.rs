@@ -1,8 +1,15 @@
-use crate::models::employee::*;
-use crate::database::{Collections, helpers::*};
+use crate::models::{employee::*, attendance::AttendanceRecord};
+use crate::commands::attendance::{
+    employee_lookup_stage, is_month_locked, month_locked_error, sync_employee_number
+};
+use crate::models::dates::{add_years, format_date, parse_date, to_bson_date};
+use crate::models::nic::{validate_employee_nic, Nic};
+use crate::commands::settings::load_retirement_settings;
+use crate::database::{Collections, Indexes, connection::ensure_indexes, helpers::*};
 use crate::AppState;
-use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime, Document, RawDocumentBuf};
 use mongodb::options::{FindOptions, UpdateOptions};
+use mongodb::Database;
 use tauri::State;
 use futures::stream::TryStreamExt;
 
@@ -17,11 +24,15 @@ pub async fn get_employees(
     let collection = db.collection::<Employee>(Collections::EMPLOYEES);
 
     let pagination = pagination.unwrap_or_default();
+    let retirement_settings = load_retirement_settings(db).await?;
+    let today = chrono::Local::now().date_naive();
 
     // Build filter document
     let mut filter_doc = doc! {};
+    let mut include_archived = false;
 
     if let Some(f) = filter {
+        include_archived = f.include_archived;
         if let Some(emp_num) = f.employee_number {
             filter_doc.insert("employeeNumber", doc! { "$regex": emp_num, "$options": "i" });
         }
@@ -45,6 +56,8 @@ pub async fn get_employees(
             filter_doc.insert("ministry", doc! { "$regex": ministry, "$options": "i" });
         }
         if let Some(nic) = f.nic_number {
+            // A whole NIC number is looked up in the 12 digit format it is stored in
+            let nic = Nic::parse(&nic).map(|n| n.to_new_format()).unwrap_or(nic);
             filter_doc.insert("nicNumber", doc! { "$regex": nic, "$options": "i" });
         }
         if let Some(gender) = f.gender {
@@ -70,9 +83,16 @@ pub async fn get_employees(
             filter_doc.insert("salaryCode", salary_code_str);
         }
         if let Some(age_range) = f.age_range {
-            filter_doc.insert("age", doc! { "$gte": age_range.min, "$lte": age_range.max });
+            // Ages go stale, so match on the birth dates giving an age in the range today
+            filter_doc.insert("dateOfBirth", doc! {
+                "$gt": to_bson_date(&add_years(&today, -(age_range.max + 1))),
+                "$lte": to_bson_date(&add_years(&today, -age_range.min)),
+            });
         }
     }
+    if !include_archived {
+        filter_doc.insert("archived", doc! { "$ne": true });
+    }
 
     // Get total count
     let total = collection.count_documents(filter_doc.clone(), None)
@@ -87,14 +107,15 @@ pub async fn get_employees(
         .build();
 
     // Find employees
-    let mut cursor = collection.find(filter_doc, find_options)
+    let mut cursor = collection.clone_with_type::<RawDocumentBuf>().find(filter_doc, find_options)
         .await
         .map_err(|e| format!("Failed to find employees: {}", e))?;
 
     let mut employees = Vec::new();
-    while let Some(employee) = cursor.try_next()
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
         .await
         .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        employee.derive_dates(&retirement_settings, today);
         employees.push(employee);
     }
 
@@ -124,13 +145,18 @@ pub async fn get_employee_by_id(
     let filter = id_filter(&id)
         .map_err(|e| format!("Invalid employee ID: {}", e))?;
 
+    let retirement_settings = load_retirement_settings(db).await?;
+
     match collection.find_one(filter, None).await {
-        Ok(Some(employee)) => Ok(EmployeeResponse {
-            success: true,
-            data: Some(employee),
-            message: None,
-            error: None,
-        }),
+        Ok(Some(mut employee)) => {
+            employee.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
+            Ok(EmployeeResponse {
+                success: true,
+                data: Some(employee),
+                message: None,
+                error: None,
+            })
+        },
         Ok(None) => Ok(EmployeeResponse {
             success: false,
             data: None,
@@ -155,9 +181,20 @@ pub async fn create_employee(
     let db = &state.db;
     let collection = db.collection::<Employee>(Collections::EMPLOYEES);
 
+    let mut employee = request.employee;
+    employee.nic_number = match validate_employee_nic(&employee.nic_number, employee.date_of_birth, employee.gender) {
+        Ok(nic_number) => nic_number,
+        Err(error) => return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(error),
+        }),
+    };
+
     // Check if employee number already exists
     let existing = collection.find_one(
-        doc! { "employeeNumber": &request.employee.employee_number },
+        doc! { "employeeNumber": &employee.employee_number },
         None
     ).await
         .map_err(|e| format!("Failed to check existing employee: {}", e))?;
@@ -171,11 +208,20 @@ pub async fn create_employee(
         });
     }
 
-    let mut employee = request.employee;
     employee.id = Some(ObjectId::new());
+    // Staff join as active, later changes go through change_employment_status
+    employee.status = EmploymentStatus::Active;
+    employee.archived = false;
+    employee.archived_at = None;
+    employee.archived_by = None;
+    employee.archive_reason = None;
     employee.created_at = Some(DateTime::now());
     employee.updated_at = Some(DateTime::now());
 
+    // Age and retirement date are always derived, whatever the client sent
+    let retirement_settings = load_retirement_settings(db).await?;
+    employee.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
+
     match collection.insert_one(&employee, None).await {
         Ok(_) => Ok(EmployeeResponse {
             success: true,
@@ -187,7 +233,8 @@ pub async fn create_employee(
             success: false,
             data: None,
             message: None,
-            error: Some(format!("Failed to create employee: {}", e)),
+            error: Some(duplicate_employee_error(&e)
+                .unwrap_or_else(|| format!("Failed to create employee: {}", e))),
         }),
     }
 }
@@ -206,10 +253,60 @@ pub async fn update_employee(
         .map_err(|e| format!("Invalid employee ID: {}", e))?;
 
     let mut employee = request.employee;
+    employee.nic_number = match validate_employee_nic(&employee.nic_number, employee.date_of_birth, employee.gender) {
+        Ok(nic_number) => nic_number,
+        Err(error) => return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(error),
+        }),
+    };
+
+    let existing = collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))?;
+
+    // A changed employee number must stay unique
+    let number_changed = existing.as_ref()
+        .map(|e| e.employee_number != employee.employee_number)
+        .unwrap_or(false);
+    if number_changed {
+        let duplicate = collection.find_one(
+            doc! { "employeeNumber": &employee.employee_number },
+            None
+        ).await
+            .map_err(|e| format!("Failed to check existing employee: {}", e))?;
+
+        if duplicate.is_some() {
+            return Ok(EmployeeResponse {
+                success: false,
+                data: None,
+                message: None,
+                error: Some("Employee number already exists".to_string()),
+            });
+        }
+    }
+
     employee.updated_at = Some(DateTime::now());
 
+    // Archiving is only changed through archive_employee and restore_employee,
+    // and status (with the date of retiring) through change_employment_status
+    if let Some(existing) = existing.as_ref() {
+        employee.status = existing.status;
+        employee.retired_date = existing.retired_date;
+        employee.archived = existing.archived;
+        employee.archived_at = existing.archived_at;
+        employee.archived_by = existing.archived_by.clone();
+        employee.archive_reason = existing.archive_reason.clone();
+    }
+
+    // Age and retirement date are always derived, whatever the client sent
+    let retirement_settings = load_retirement_settings(db).await?;
+    employee.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
+
     let update_doc = doc! {
-        "$set": mongodb::bson::to_bson(&employee)
+        "$set": to_stored_document(&employee)
             .map_err(|e| format!("Failed to serialize employee: {}", e))?
     };
 
@@ -225,6 +322,10 @@ pub async fn update_employee(
                     error: None,
                 })
             } else {
+                // Keep the number denormalised on attendance and leave in step
+                if number_changed {
+                    sync_employee_number(db, &id, &employee.employee_number).await?;
+                }
                 Ok(EmployeeResponse {
                     success: true,
                     data: Some(employee),
@@ -237,16 +338,19 @@ pub async fn update_employee(
             success: false,
             data: None,
             message: None,
-            error: Some(format!("Failed to update employee: {}", e)),
+            error: Some(duplicate_employee_error(&e)
+                .unwrap_or_else(|| format!("Failed to update employee: {}", e))),
         }),
     }
 }
 
-/// Delete an employee
+/// Delete an employee. Attendance and leave records of the employee are handled by
+/// `policy`, which defaults to refusing the deletion while any exist.
 #[tauri::command]
 pub async fn delete_employee(
     state: State<'_, AppState>,
     id: String,
+    policy: Option<DeletePolicy>,
 ) -> Result<EmployeeResponse, String> {
     let db = &state.db;
     let collection = db.collection::<Employee>(Collections::EMPLOYEES);
@@ -254,31 +358,354 @@ pub async fn delete_employee(
     let filter = id_filter(&id)
         .map_err(|e| format!("Invalid employee ID: {}", e))?;
 
+    let delete_error = |error: String| EmployeeResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error),
+    };
+
+    let existing = collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))?;
+    if existing.is_none() {
+        return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: Some("Employee not found".to_string()),
+            error: None,
+        });
+    }
+
+    let dependents_filter = doc! { "employeeId": &id };
+    let mut dependents = Vec::new();
+    for (name, _) in DEPENDENT_COLLECTIONS {
+        let count = db.collection::<Document>(name)
+            .count_documents(dependents_filter.clone(), None)
+            .await
+            .map_err(|e| format!("Failed to count {} records: {}", name, e))?;
+        if count > 0 {
+            dependents.push(format!("{} {}", count, name.replace('_', " ")));
+        }
+    }
+
+    let policy = policy.unwrap_or(DeletePolicy::Block);
+    match policy {
+        DeletePolicy::Block => {
+            if !dependents.is_empty() {
+                return Ok(delete_error(format!(
+                    "Employee has dependent records ({}). Archive or cascade them to delete the employee",
+                    dependents.join(", ")
+                )));
+            }
+        },
+        DeletePolicy::Cascade | DeletePolicy::Archive => {
+            if let Some(error) = locked_attendance_error(db, dependents_filter.clone()).await? {
+                return Ok(delete_error(error));
+            }
+        },
+    }
+
+    // Delete the employee before the dependents, so a failure leaves them in place
     match collection.delete_one(filter, None).await {
-        Ok(result) => {
-            if result.deleted_count == 0 {
-                Ok(EmployeeResponse {
-                    success: false,
-                    data: None,
-                    message: Some("Employee not found".to_string()),
-                    error: None,
-                })
-            } else {
-                Ok(EmployeeResponse {
-                    success: true,
-                    data: None,
-                    message: Some("Employee deleted successfully".to_string()),
-                    error: None,
-                })
+        Ok(result) if result.deleted_count == 0 => {
+            return Ok(EmployeeResponse {
+                success: false,
+                data: None,
+                message: Some("Employee not found".to_string()),
+                error: None,
+            });
+        },
+        Ok(_) => {},
+        Err(e) => return Ok(delete_error(format!("Failed to delete employee: {}", e))),
+    }
+
+    match policy {
+        DeletePolicy::Block => {},
+        DeletePolicy::Cascade => {
+            for (name, _) in DEPENDENT_COLLECTIONS {
+                db.collection::<Document>(name)
+                    .delete_many(dependents_filter.clone(), None)
+                    .await
+                    .map_err(|e| format!("Failed to delete {} records: {}", name, e))?;
             }
         },
-        Err(e) => Ok(EmployeeResponse {
+        DeletePolicy::Archive => {
+            for (name, archive) in DEPENDENT_COLLECTIONS {
+                archive_documents(db, name, archive, dependents_filter.clone()).await?;
+            }
+        },
+    }
+
+    Ok(EmployeeResponse {
+        success: true,
+        data: None,
+        message: Some("Employee deleted successfully".to_string()),
+        error: None,
+    })
+}
+
+/// Archive an employee who has left, keeping the record and its history
+#[tauri::command]
+pub async fn archive_employee(
+    state: State<'_, AppState>,
+    id: String,
+    reason: String,
+    archived_by: String,
+) -> Result<EmployeeResponse, String> {
+    if reason.trim().is_empty() || archived_by.trim().is_empty() {
+        return Ok(EmployeeResponse {
             success: false,
             data: None,
             message: None,
-            error: Some(format!("Failed to delete employee: {}", e)),
+            error: Some("A reason and the user archiving the employee are required".to_string()),
+        });
+    }
+
+    let update_doc = doc! {
+        "$set": {
+            "archived": true,
+            "archivedAt": DateTime::now(),
+            "archivedBy": archived_by.trim(),
+            "archiveReason": reason.trim(),
+            "updatedAt": DateTime::now()
+        }
+    };
+
+    set_archived(&state.db, &id, false, update_doc).await
+}
+
+/// Restore an archived employee
+#[tauri::command]
+pub async fn restore_employee(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<EmployeeResponse, String> {
+    let update_doc = doc! {
+        "$set": { "archived": false, "updatedAt": DateTime::now() },
+        "$unset": { "archivedAt": "", "archivedBy": "", "archiveReason": "" }
+    };
+
+    set_archived(&state.db, &id, true, update_doc).await
+}
+
+/// Move an employee to a new employment status, recording the change in the status history
+#[tauri::command]
+pub async fn change_employment_status(
+    state: State<'_, AppState>,
+    id: String,
+    request: ChangeEmploymentStatusRequest,
+) -> Result<EmployeeResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Employee>(Collections::EMPLOYEES);
+    let history_collection = db.collection::<EmploymentStatusChange>(Collections::EMPLOYMENT_STATUS_HISTORY);
+
+    let status_error = |error: String| Ok(EmployeeResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error),
+    });
+
+    let changed_by = request.changed_by.trim();
+    if changed_by.is_empty() {
+        return status_error("The user changing the status is required".to_string());
+    }
+
+    let effective_date = match parse_date(&request.effective_date) {
+        Some(date) => date,
+        None => return status_error("Invalid effective date. Use dd-MM-yyyy".to_string()),
+    };
+
+    let mut filter = id_filter(&id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let existing = match collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: Some("Employee not found".to_string()),
+            error: None,
         }),
+    };
+
+    if !existing.status.can_transition_to(request.status) {
+        return status_error(format!(
+            "Cannot change employment status from {} to {}",
+            status_label(existing.status),
+            status_label(request.status)
+        ));
+    }
+
+    let mut set_doc = doc! {
+        "status": mongodb::bson::to_bson(&request.status)
+            .map_err(|e| format!("Failed to serialize status: {}", e))?,
+        "updatedAt": DateTime::now()
+    };
+    match request.status {
+        EmploymentStatus::TransferredOut => {
+            set_doc.insert("dateOfTransfer", to_bson_date(&effective_date));
+        },
+        EmploymentStatus::Retired => {
+            set_doc.insert("retiredDate", to_bson_date(&effective_date));
+        },
+        _ => {},
+    }
+
+    let change = EmploymentStatusChange {
+        id: Some(ObjectId::new()),
+        employee_id: id.clone(),
+        from_status: existing.status,
+        to_status: request.status,
+        effective_date: format_date(&effective_date),
+        changed_by: changed_by.to_string(),
+        reason: request.reason
+            .map(|r| r.trim().to_string())
+            .filter(|r| !r.is_empty()),
+        created_at: Some(DateTime::now()),
+    };
+
+    // Record the history first and withdraw it if the status cannot be changed,
+    // so a status change is never left without its history entry
+    history_collection.insert_one(&change, None)
+        .await
+        .map_err(|e| format!("Failed to record status change: {}", e))?;
+
+    // Only apply the change if no one else changed the status in the meantime
+    filter.insert("status", mongodb::bson::to_bson(&existing.status)
+        .map_err(|e| format!("Failed to serialize status: {}", e))?);
+    let options = mongodb::options::FindOneAndUpdateOptions::builder()
+        .return_document(mongodb::options::ReturnDocument::After)
+        .build();
+
+    let updated = collection.find_one_and_update(filter, doc! { "$set": set_doc }, options).await;
+    let mut employee = match updated {
+        Ok(Some(employee)) => employee,
+        other => {
+            history_collection.delete_one(doc! { "_id": change.id }, None)
+                .await
+                .map_err(|e| format!("Failed to withdraw status change: {}", e))?;
+            return match other {
+                Err(e) => Err(format!("Failed to update employee: {}", e)),
+                _ => status_error("Employment status was changed by someone else. Reload and try again".to_string()),
+            };
+        },
+    };
+
+    let retirement_settings = load_retirement_settings(db).await?;
+    employee.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
+
+    Ok(EmployeeResponse {
+        success: true,
+        data: Some(employee),
+        message: Some(format!("Employment status changed to {}", status_label(request.status))),
+        error: None,
+    })
+}
+
+/// Get the status changes of an employee, oldest first
+#[tauri::command]
+pub async fn get_employment_status_history(
+    state: State<'_, AppState>,
+    employee_id: String,
+) -> Result<Vec<EmploymentStatusChange>, String> {
+    let collection = state.db.collection::<EmploymentStatusChange>(Collections::EMPLOYMENT_STATUS_HISTORY);
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "createdAt": 1 })
+        .build();
+
+    let mut cursor = collection.find(doc! { "employeeId": &employee_id }, find_options)
+        .await
+        .map_err(|e| format!("Failed to find status history: {}", e))?;
+
+    let mut history = Vec::new();
+    while let Some(change) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate status history: {}", e))? {
+        history.push(change);
+    }
+
+    Ok(history)
+}
+
+/// List attendance records, leave applications and ledger entries whose employee no longer exists
+#[tauri::command]
+pub async fn get_orphaned_records(
+    state: State<'_, AppState>,
+) -> Result<OrphanedRecordsResponse, String> {
+    let orphans = find_orphaned_records(&state.db).await?;
+
+    Ok(OrphanedRecordsResponse {
+        success: true,
+        message: Some(format!("Found {} orphaned records", orphans.len())),
+        data: Some(orphans),
+        error: None,
+    })
+}
+
+/// Delete (cascade) or archive the records whose employee no longer exists
+#[tauri::command]
+pub async fn cleanup_orphaned_records(
+    state: State<'_, AppState>,
+    policy: DeletePolicy,
+) -> Result<OrphanedRecordsResponse, String> {
+    let db = &state.db;
+
+    if policy == DeletePolicy::Block {
+        return Ok(OrphanedRecordsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("Choose cascade or archive to clean up orphaned records".to_string()),
+        });
+    }
+
+    let orphans = find_orphaned_records(db).await?;
+
+    let orphaned_attendance: Vec<ObjectId> = orphans.iter()
+        .filter(|o| o.collection == Collections::ATTENDANCE)
+        .filter_map(|o| string_to_object_id(&o.id).ok())
+        .collect();
+    if let Some(error) = locked_attendance_error(db, doc! { "_id": { "$in": orphaned_attendance } }).await? {
+        return Ok(OrphanedRecordsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(error),
+        });
+    }
+
+    for (name, archive) in DEPENDENT_COLLECTIONS {
+        let ids: Vec<ObjectId> = orphans.iter()
+            .filter(|o| o.collection == name)
+            .filter_map(|o| string_to_object_id(&o.id).ok())
+            .collect();
+        if ids.is_empty() {
+            continue;
+        }
+
+        let filter = doc! { "_id": { "$in": ids } };
+        if policy == DeletePolicy::Archive {
+            archive_documents(db, name, archive, filter).await?;
+        } else {
+            db.collection::<Document>(name)
+                .delete_many(filter, None)
+                .await
+                .map_err(|e| format!("Failed to delete {} records: {}", name, e))?;
+        }
     }
+
+    let action = if policy == DeletePolicy::Archive { "Archived" } else { "Deleted" };
+    Ok(OrphanedRecordsResponse {
+        success: true,
+        message: Some(format!("{} {} orphaned records", action, orphans.len())),
+        data: Some(orphans),
+        error: None,
+    })
 }
 
 /// Search employees
@@ -287,13 +714,15 @@ pub async fn search_employees(
     state: State<'_, AppState>,
     query: String,
     pagination: Option<PaginationOptions>,
+    include_archived: Option<bool>,
 ) -> Result<EmployeesResponse, String> {
     let db = &state.db;
     let collection = db.collection::<Employee>(Collections::EMPLOYEES);
 
     let pagination = pagination.unwrap_or_default();
 
-    // Create search filter
+    // Create search filter. A whole NIC number is searched in the format it is stored in.
+    let query = Nic::parse(&query).map(|n| n.to_new_format()).unwrap_or(query);
     let search_options = SearchOptions {
         query,
         fields: vec![
@@ -307,7 +736,10 @@ pub async fn search_employees(
         case_sensitive: false,
     };
 
-    let filter_doc = search_options.to_filter();
+    let mut filter_doc = search_options.to_filter();
+    if !include_archived.unwrap_or(false) {
+        filter_doc.insert("archived", doc! { "$ne": true });
+    }
 
     // Get total count
     let total = collection.count_documents(filter_doc.clone(), None)
@@ -322,14 +754,18 @@ pub async fn search_employees(
         .build();
 
     // Find employees
-    let mut cursor = collection.find(filter_doc, find_options)
+    let mut cursor = collection.clone_with_type::<RawDocumentBuf>().find(filter_doc, find_options)
         .await
         .map_err(|e| format!("Failed to search employees: {}", e))?;
 
+    let retirement_settings = load_retirement_settings(db).await?;
+    let today = chrono::Local::now().date_naive();
+
     let mut employees = Vec::new();
-    while let Some(employee) = cursor.try_next()
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
         .await
         .map_err(|e| format!("Failed to iterate search results: {}", e))? {
+        employee.derive_dates(&retirement_settings, today);
         employees.push(employee);
     }
 
@@ -345,4 +781,485 @@ pub async fn search_employees(
         message: None,
         error: None,
     })
-}
\ No newline at end of file
+}
+
+/// Convert employee dates stored as dd-MM-yyyy strings into BSON dates. Values that
+/// cannot be read are reported; optional ones are moved aside into `legacyDates`.
+#[tauri::command]
+pub async fn migrate_employee_dates(
+    state: State<'_, AppState>,
+) -> Result<DateMigrationResponse, String> {
+    run_employee_date_migration(&state.db).await
+}
+
+/// Run the employee date migration. It only touches dates still stored as strings,
+/// so it is run on every start and can be run again from the UI.
+pub async fn run_employee_date_migration(db: &Database) -> Result<DateMigrationResponse, String> {
+    let collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    // Only employees with a date still stored as a string
+    let string_dates: Vec<Document> = EMPLOYEE_DATE_FIELDS.iter()
+        .map(|(field, _)| doc! { *field: { "$type": "string" } })
+        .collect();
+
+    let mut cursor = collection.find(doc! { "$or": string_dates }, None)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut employees_updated = 0;
+    let mut invalid_dates = Vec::new();
+
+    while let Some(document) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        let id = match document.get_object_id("_id") {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+
+        let mut set_doc = Document::new();
+        let mut unset_doc = Document::new();
+
+        for (field, required) in EMPLOYEE_DATE_FIELDS {
+            let value = match field.split_once('.') {
+                Some((parent, child)) => document.get_document(parent)
+                    .ok()
+                    .and_then(|d| d.get_str(child).ok()),
+                None => document.get_str(field).ok(),
+            };
+            let value = match value {
+                Some(value) => value,
+                None => continue, // Already a date, or not set
+            };
+
+            if value.trim().is_empty() {
+                if !required {
+                    unset_doc.insert(field, "");
+                }
+                continue;
+            }
+
+            match parse_date(value) {
+                Some(date) => {
+                    set_doc.insert(field, to_bson_date(&date));
+                },
+                None => {
+                    invalid_dates.push(InvalidEmployeeDate {
+                        employee_id: object_id_to_string(&id),
+                        employee_number: document.get_str("employeeNumber").unwrap_or_default().to_string(),
+                        field: field.to_string(),
+                        value: value.to_string(),
+                    });
+                    if !required {
+                        set_doc.insert(format!("legacyDates.{}", field.replace('.', "_")), value);
+                        unset_doc.insert(field, "");
+                    }
+                },
+            }
+        }
+
+        if set_doc.is_empty() && unset_doc.is_empty() {
+            continue;
+        }
+
+        let mut update_doc = Document::new();
+        if !set_doc.is_empty() {
+            update_doc.insert("$set", set_doc);
+        }
+        if !unset_doc.is_empty() {
+            update_doc.insert("$unset", unset_doc);
+        }
+
+        collection.update_one(doc! { "_id": id }, update_doc, None)
+            .await
+            .map_err(|e| format!("Failed to migrate employee dates: {}", e))?;
+        employees_updated += 1;
+    }
+
+    Ok(DateMigrationResponse {
+        success: true,
+        message: Some(format!(
+            "Migrated dates of {} employees, {} dates could not be read",
+            employees_updated,
+            invalid_dates.len()
+        )),
+        employees_updated,
+        invalid_dates,
+        error: None,
+    })
+}
+
+/// Convert free-text employment statuses stored before statuses were structured.
+/// Values that match no status are reported and left for the user to correct.
+#[tauri::command]
+pub async fn migrate_employee_statuses(
+    state: State<'_, AppState>,
+) -> Result<StatusMigrationResponse, String> {
+    run_employee_status_migration(&state.db).await
+}
+
+/// Run the employment status migration. It only touches statuses that are not yet
+/// one of the structured values, so it is run on every start and can be run again from the UI.
+pub async fn run_employee_status_migration(db: &Database) -> Result<StatusMigrationResponse, String> {
+    let collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    let statuses = EmploymentStatus::ALL.iter()
+        .map(mongodb::bson::to_bson)
+        .collect::<Result<Vec<Bson>, _>>()
+        .map_err(|e| format!("Failed to serialize employment statuses: {}", e))?;
+
+    let find_options = FindOptions::builder()
+        .projection(doc! { "employeeNumber": 1, "status": 1 })
+        .build();
+
+    let mut cursor = collection.find(doc! { "status": { "$nin": statuses } }, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut employees_updated = 0;
+    let mut invalid_statuses = Vec::new();
+
+    while let Some(document) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        let id = match document.get_object_id("_id") {
+            Ok(id) => id,
+            Err(_) => continue,
+        };
+
+        let value = match document.get("status") {
+            None | Some(Bson::Null) => String::new(),
+            Some(Bson::String(value)) => value.clone(),
+            Some(other) => other.to_string(),
+        };
+
+        match EmploymentStatus::parse(&value) {
+            Some(status) => {
+                let status = mongodb::bson::to_bson(&status)
+                    .map_err(|e| format!("Failed to serialize employment status: {}", e))?;
+                collection.update_one(doc! { "_id": id }, doc! { "$set": { "status": status } }, None)
+                    .await
+                    .map_err(|e| format!("Failed to migrate employment status: {}", e))?;
+                employees_updated += 1;
+            },
+            None => invalid_statuses.push(InvalidEmployeeStatus {
+                employee_id: object_id_to_string(&id),
+                employee_number: document.get_str("employeeNumber").unwrap_or_default().to_string(),
+                value,
+            }),
+        }
+    }
+
+    Ok(StatusMigrationResponse {
+        success: true,
+        message: Some(format!(
+            "Migrated statuses of {} employees, {} statuses could not be read",
+            employees_updated,
+            invalid_statuses.len()
+        )),
+        employees_updated,
+        invalid_statuses,
+        error: None,
+    })
+}
+
+/// List employees sharing an employee number, NIC number or email address, after
+/// trying again to build the unique indexes those duplicates keep from being built
+#[tauri::command]
+pub async fn get_duplicate_employees(
+    state: State<'_, AppState>,
+) -> Result<DuplicateEmployeesResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Document>(Collections::EMPLOYEES);
+
+    let index_errors = ensure_indexes(db).await;
+
+    // Blank NIC numbers and emails are not unique, and emails differing only in case clash
+    let fields = [
+        ("employeeNumber", Bson::from("$employeeNumber"), doc! {}),
+        ("nicNumber", Bson::from("$nicNumber"), doc! { "nicNumber": { "$gt": "" } }),
+        ("emailAddress", Bson::from(doc! { "$toLower": "$emailAddress" }), doc! { "emailAddress": { "$gt": "" } }),
+    ];
+
+    let mut duplicates = Vec::new();
+    for (field, key, match_doc) in fields {
+        let pipeline = vec![
+            doc! { "$match": match_doc },
+            doc! {
+                "$group": {
+                    "_id": key,
+                    "count": { "$sum": 1 },
+                    "employees": {
+                        "$push": {
+                            "employeeId": { "$toString": "$_id" },
+                            "employeeNumber": "$employeeNumber",
+                            "fullName": "$fullName"
+                        }
+                    }
+                }
+            },
+            doc! { "$match": { "count": { "$gt": 1 } } },
+            doc! { "$sort": { "_id": 1 } },
+        ];
+
+        let mut cursor = collection.aggregate(pipeline, None)
+            .await
+            .map_err(|e| format!("Failed to find duplicate employees: {}", e))?;
+
+        while let Some(group) = cursor.try_next()
+            .await
+            .map_err(|e| format!("Failed to iterate duplicate employees: {}", e))? {
+            let employees: Vec<DuplicateEmployee> = match group.get_array("employees") {
+                Ok(employees) => mongodb::bson::from_bson(Bson::Array(employees.clone()))
+                    .map_err(|e| format!("Failed to read duplicate employees: {}", e))?,
+                Err(_) => continue,
+            };
+            duplicates.push(DuplicateEmployeeGroup {
+                field: field.to_string(),
+                value: group.get_str("_id").unwrap_or_default().to_string(),
+                employees,
+            });
+        }
+    }
+
+    Ok(DuplicateEmployeesResponse {
+        success: true,
+        message: Some(format!(
+            "{} duplicate values, {} indexes could not be built",
+            duplicates.len(),
+            index_errors.len()
+        )),
+        data: Some(DuplicateEmployeeReport { index_errors, duplicates }),
+        error: None,
+    })
+}
+
+// Helper functions for employee dependents
+
+/// Apply an archive or restore update to an employee currently in the `from_archived` state
+async fn set_archived(
+    db: &Database,
+    id: &str,
+    from_archived: bool,
+    update_doc: Document,
+) -> Result<EmployeeResponse, String> {
+    let collection = db.collection::<Employee>(Collections::EMPLOYEES);
+
+    let mut filter = id_filter(id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let existing = match collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: Some("Employee not found".to_string()),
+            error: None,
+        }),
+    };
+
+    if existing.archived != from_archived {
+        let state = if existing.archived { "already archived" } else { "not archived" };
+        return Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Employee is {}", state)),
+        });
+    }
+
+    filter.insert("archived", if from_archived { doc! { "$eq": true } } else { doc! { "$ne": true } });
+    let options = mongodb::options::FindOneAndUpdateOptions::builder()
+        .return_document(mongodb::options::ReturnDocument::After)
+        .build();
+
+    let employee = collection.find_one_and_update(filter, update_doc, options)
+        .await
+        .map_err(|e| format!("Failed to update employee: {}", e))?;
+
+    let retirement_settings = load_retirement_settings(db).await?;
+    let employee = employee.map(|mut employee| {
+        employee.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
+        employee
+    });
+
+    let action = if from_archived { "restored" } else { "archived" };
+    match employee {
+        Some(employee) => Ok(EmployeeResponse {
+            success: true,
+            data: Some(employee),
+            message: Some(format!("Employee {} successfully", action)),
+            error: None,
+        }),
+        None => Ok(EmployeeResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Employee could not be {}", action)),
+        }),
+    }
+}
+
+/// Employee date fields, and whether the employee cannot be read without them
+const EMPLOYEE_DATE_FIELDS: [(&str, bool); 9] = [
+    ("dateOfBirth", true),
+    ("firstAppointmentDate", true),
+    ("gradeAppointmentDate.gradeIII", false),
+    ("gradeAppointmentDate.gradeII", false),
+    ("gradeAppointmentDate.gradeI", false),
+    ("gradeAppointmentDate.gradeSupra", false),
+    ("dateOfArrivalVDS", false),
+    ("dateOfTransfer", false),
+    ("retiredDate", false),
+];
+
+/// Collections holding records that belong to an employee, with their archive collections
+const DEPENDENT_COLLECTIONS: [(&str, &str); 4] = [
+    (Collections::ATTENDANCE, Collections::ATTENDANCE_ARCHIVE),
+    (Collections::LEAVES, Collections::LEAVES_ARCHIVE),
+    (Collections::LEAVE_LEDGER, Collections::LEAVE_LEDGER_ARCHIVE),
+    (Collections::EMPLOYMENT_STATUS_HISTORY, Collections::EMPLOYMENT_STATUS_HISTORY_ARCHIVE),
+];
+
+/// The error for the first locked month among the attendance records matching
+/// `filter`. Locked months keep their attendance, so it is neither deleted nor archived.
+async fn locked_attendance_error(db: &Database, filter: Document) -> Result<Option<String>, String> {
+    let attendance = db.collection::<AttendanceRecord>(Collections::ATTENDANCE);
+
+    let mut cursor = attendance.find(filter, None)
+        .await
+        .map_err(|e| format!("Failed to find attendance records: {}", e))?;
+
+    while let Some(record) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate attendance records: {}", e))? {
+        if is_month_locked(db, record.month, record.year).await? {
+            return Ok(Some(month_locked_error(record.month, record.year)));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Move the matching documents into an archive collection, stamped with the archive time
+async fn archive_documents(
+    db: &Database,
+    collection: &str,
+    archive: &str,
+    filter: Document,
+) -> Result<u64, String> {
+    let source = db.collection::<Document>(collection);
+
+    let mut cursor = source.find(filter, None)
+        .await
+        .map_err(|e| format!("Failed to find {} records: {}", collection, e))?;
+
+    let mut documents = Vec::new();
+    while let Some(mut document) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate {} records: {}", collection, e))? {
+        document.insert("archivedAt", DateTime::now());
+        documents.push(document);
+    }
+
+    if documents.is_empty() {
+        return Ok(0);
+    }
+
+    let ids: Vec<Bson> = documents.iter()
+        .filter_map(|d| d.get("_id").cloned())
+        .collect();
+
+    // Copy before deleting so an interrupted archive never loses records
+    db.collection::<Document>(archive)
+        .insert_many(&documents, None)
+        .await
+        .map_err(|e| format!("Failed to archive {} records: {}", collection, e))?;
+
+    let result = source.delete_many(doc! { "_id": { "$in": ids } }, None)
+        .await
+        .map_err(|e| format!("Failed to remove archived {} records: {}", collection, e))?;
+
+    Ok(result.deleted_count)
+}
+
+async fn find_orphaned_records(db: &Database) -> Result<Vec<OrphanedRecord>, String> {
+    let mut orphans = Vec::new();
+
+    for (name, _) in DEPENDENT_COLLECTIONS {
+        let pipeline = vec![
+            employee_lookup_stage(),
+            doc! { "$match": { "employee": { "$size": 0 } } },
+            doc! { "$project": { "employee": 0, "records": 0 } },
+        ];
+
+        let mut cursor = db.collection::<Document>(name).aggregate(pipeline, None)
+            .await
+            .map_err(|e| format!("Failed to find orphaned {} records: {}", name, e))?;
+
+        while let Some(document) = cursor.try_next()
+            .await
+            .map_err(|e| format!("Failed to iterate orphaned {} records: {}", name, e))? {
+            let text = |key: &str| document.get(key).map(|v| match v {
+                Bson::String(s) => s.clone(),
+                other => other.to_string(),
+            });
+
+            let details = match name {
+                Collections::ATTENDANCE => format!(
+                    "Attendance {}/{}",
+                    text("month").unwrap_or_default(),
+                    text("year").unwrap_or_default()
+                ),
+                Collections::LEAVES => format!(
+                    "Leave {} to {}",
+                    text("startDate").unwrap_or_default(),
+                    text("endDate").unwrap_or_default()
+                ),
+                Collections::EMPLOYMENT_STATUS_HISTORY => format!(
+                    "Status change {} to {} on {}",
+                    text("fromStatus").unwrap_or_default(),
+                    text("toStatus").unwrap_or_default(),
+                    text("effectiveDate").unwrap_or_default()
+                ),
+                _ => format!(
+                    "Ledger {} {} {}",
+                    text("year").unwrap_or_default(),
+                    text("leaveType").unwrap_or_default(),
+                    text("entryType").unwrap_or_default()
+                ),
+            };
+
+            orphans.push(OrphanedRecord {
+                collection: name.to_string(),
+                id: document.get_object_id("_id").map(|id| object_id_to_string(&id)).unwrap_or_default(),
+                employee_id: text("employeeId").unwrap_or_default(),
+                employee_number: text("employeeNumber").filter(|n| !n.is_empty()),
+                details,
+            });
+        }
+    }
+
+    Ok(orphans)
+}
+
+/// A field-specific message for a write rejected by one of the unique employee indexes
+fn duplicate_employee_error(error: &mongodb::error::Error) -> Option<String> {
+    let message = match duplicate_key_index(error)?.as_str() {
+        Indexes::EMPLOYEE_NUMBER => "Employee number already exists",
+        Indexes::NIC_NUMBER => "An employee with this NIC number already exists",
+        Indexes::EMAIL_ADDRESS => "An employee with this email address already exists",
+        _ => return None,
+    };
+    Some(message.to_string())
+}
+
+/// The status as shown to users, matching its stored value
+fn status_label(status: EmploymentStatus) -> String {
+    mongodb::bson::to_bson(&status)
+        .ok()
+        .and_then(|s| s.as_str().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
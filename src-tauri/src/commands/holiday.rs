@@ -0,0 +1,544 @@
+use crate::models::{holiday::*, dates::{days_in_month, format_date, parse_date}};
+use crate::database::{Collections, helpers::*};
+use crate::AppState;
+use mongodb::bson::{doc, oid::ObjectId, DateTime};
+use mongodb::options::FindOptions;
+use mongodb::Database;
+use tauri::State;
+use futures::stream::TryStreamExt;
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashSet;
+use std::ops::RangeInclusive;
+
+/// Get holidays for a year, optionally limited to one month
+#[tauri::command]
+pub async fn get_holidays(
+    state: State<'_, AppState>,
+    year: i32,
+    month: Option<i32>,
+) -> Result<Vec<Holiday>, String> {
+    let mut filter_doc = doc! { "year": year };
+    if let Some(month) = month {
+        filter_doc.insert("month", month);
+    }
+
+    find_holidays(&state.db, filter_doc).await
+}
+
+/// Create a holiday
+#[tauri::command]
+pub async fn create_holiday(
+    state: State<'_, AppState>,
+    holiday: Holiday,
+) -> Result<HolidayResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Holiday>(Collections::HOLIDAYS);
+
+    let mut holiday = holiday;
+    if let Err(e) = normalize_holiday(&mut holiday) {
+        return Ok(holiday_error(&e));
+    }
+
+    let existing = collection.find_one(doc! { "date": &holiday.date, "name": &holiday.name }, None)
+        .await
+        .map_err(|e| format!("Failed to check existing holiday: {}", e))?;
+
+    if existing.is_some() {
+        return Ok(holiday_error("Holiday already exists for this date"));
+    }
+
+    holiday.id = Some(ObjectId::new());
+    holiday.created_at = Some(DateTime::now());
+    holiday.updated_at = Some(DateTime::now());
+
+    match collection.insert_one(&holiday, None).await {
+        Ok(_) => Ok(HolidayResponse {
+            success: true,
+            data: Some(holiday),
+            message: Some("Holiday created successfully".to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(holiday_error(&format!("Failed to create holiday: {}", e))),
+    }
+}
+
+/// Update a holiday
+#[tauri::command]
+pub async fn update_holiday(
+    state: State<'_, AppState>,
+    id: String,
+    holiday: Holiday,
+) -> Result<HolidayResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Holiday>(Collections::HOLIDAYS);
+
+    let filter = id_filter(&id)
+        .map_err(|e| format!("Invalid holiday ID: {}", e))?;
+
+    let mut holiday = holiday;
+    if let Err(e) = normalize_holiday(&mut holiday) {
+        return Ok(holiday_error(&e));
+    }
+    holiday.updated_at = Some(DateTime::now());
+
+    let update_doc = doc! {
+        "$set": {
+            "date": &holiday.date,
+            "name": &holiday.name,
+            "holidayType": mongodb::bson::to_bson(&holiday.holiday_type)
+                .map_err(|e| format!("Failed to serialize holiday type: {}", e))?,
+            "year": holiday.year,
+            "month": holiday.month,
+            "day": holiday.day,
+            "notes": holiday.notes.clone(),
+            "updatedAt": DateTime::now()
+        }
+    };
+
+    match collection.update_one(filter, update_doc, None).await {
+        Ok(result) => {
+            if result.matched_count == 0 {
+                Ok(HolidayResponse {
+                    success: false,
+                    data: None,
+                    message: Some("Holiday not found".to_string()),
+                    error: None,
+                })
+            } else {
+                holiday.id = Some(string_to_object_id(&id)
+                    .map_err(|e| format!("Invalid holiday ID: {}", e))?);
+                Ok(HolidayResponse {
+                    success: true,
+                    data: Some(holiday),
+                    message: Some("Holiday updated successfully".to_string()),
+                    error: None,
+                })
+            }
+        },
+        Err(e) => Ok(holiday_error(&format!("Failed to update holiday: {}", e))),
+    }
+}
+
+/// Delete a holiday
+#[tauri::command]
+pub async fn delete_holiday(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<HolidayResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Holiday>(Collections::HOLIDAYS);
+
+    let filter = id_filter(&id)
+        .map_err(|e| format!("Invalid holiday ID: {}", e))?;
+
+    match collection.delete_one(filter, None).await {
+        Ok(result) => {
+            if result.deleted_count == 0 {
+                Ok(HolidayResponse {
+                    success: false,
+                    data: None,
+                    message: Some("Holiday not found".to_string()),
+                    error: None,
+                })
+            } else {
+                Ok(HolidayResponse {
+                    success: true,
+                    data: None,
+                    message: Some("Holiday deleted successfully".to_string()),
+                    error: None,
+                })
+            }
+        },
+        Err(e) => Ok(holiday_error(&format!("Failed to delete holiday: {}", e))),
+    }
+}
+
+/// Import holidays from the contents of an iCalendar (.ics) file
+#[tauri::command]
+pub async fn import_holidays_ics(
+    state: State<'_, AppState>,
+    content: String,
+    default_type: Option<HolidayType>,
+) -> Result<HolidayImportResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<Holiday>(Collections::HOLIDAYS);
+
+    let parsed = parse_ics(&content, default_type.unwrap_or(HolidayType::Public));
+
+    if parsed.is_empty() {
+        return Ok(HolidayImportResponse {
+            success: false,
+            data: None,
+            imported: 0,
+            skipped: 0,
+            message: None,
+            error: Some("No holidays found in the calendar file".to_string()),
+        });
+    }
+
+    let mut imported = Vec::new();
+    let mut skipped = 0;
+    let mut seen = HashSet::new();
+
+    for mut holiday in parsed {
+        // The same event can appear more than once in a calendar file
+        if !seen.insert((holiday.date.clone(), holiday.name.clone())) {
+            skipped += 1;
+            continue;
+        }
+
+        let existing = collection.find_one(doc! { "date": &holiday.date, "name": &holiday.name }, None)
+            .await
+            .map_err(|e| format!("Failed to check existing holiday: {}", e))?;
+
+        if existing.is_some() {
+            skipped += 1;
+            continue;
+        }
+
+        holiday.id = Some(ObjectId::new());
+        holiday.created_at = Some(DateTime::now());
+        holiday.updated_at = Some(DateTime::now());
+        imported.push(holiday);
+    }
+
+    if !imported.is_empty() {
+        collection.insert_many(&imported, None)
+            .await
+            .map_err(|e| format!("Failed to import holidays: {}", e))?;
+    }
+
+    Ok(HolidayImportResponse {
+        success: true,
+        imported: imported.len() as u64,
+        skipped,
+        message: Some(format!("Imported {} holidays, skipped {} existing", imported.len(), skipped)),
+        data: Some(imported),
+        error: None,
+    })
+}
+
+/// Get the working days of a month, excluding weekends and holidays
+#[tauri::command]
+pub async fn get_working_days(
+    state: State<'_, AppState>,
+    month: i32,
+    year: i32,
+) -> Result<WorkingDays, String> {
+    working_days_in_month(&state.db, month, year).await
+}
+
+// Helper functions for holidays
+
+fn holiday_error(error: &str) -> HolidayResponse {
+    HolidayResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error.to_string()),
+    }
+}
+
+/// Validate the holiday and fill in the fields derived from its date
+fn normalize_holiday(holiday: &mut Holiday) -> Result<(), String> {
+    let date = parse_date(&holiday.date)
+        .ok_or_else(|| "Invalid holiday date. Use dd-MM-yyyy".to_string())?;
+
+    if holiday.name.trim().is_empty() {
+        return Err("Holiday name is required".to_string());
+    }
+
+    holiday.date = format_date(&date);
+    holiday.name = holiday.name.trim().to_string();
+    holiday.year = date.year();
+    holiday.month = date.month() as i32;
+    holiday.day = date.day() as i32;
+    Ok(())
+}
+
+async fn find_holidays(
+    db: &Database,
+    filter: mongodb::bson::Document,
+) -> Result<Vec<Holiday>, String> {
+    let collection = db.collection::<Holiday>(Collections::HOLIDAYS);
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "year": 1, "month": 1, "day": 1 })
+        .build();
+
+    let mut cursor = collection.find(filter, find_options)
+        .await
+        .map_err(|e| format!("Failed to find holidays: {}", e))?;
+
+    let mut holidays = Vec::new();
+    while let Some(holiday) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate holidays: {}", e))? {
+        holidays.push(holiday);
+    }
+
+    Ok(holidays)
+}
+
+/// Whether the date falls on a Saturday or Sunday
+pub(crate) fn is_weekend(date: NaiveDate) -> bool {
+    matches!(date.weekday(), Weekday::Sat | Weekday::Sun)
+}
+
+/// Whether the office is open on the date, given the office-closing holidays
+pub(crate) fn is_working_day(date: NaiveDate, holidays: &HashSet<NaiveDate>) -> bool {
+    !is_weekend(date) && !holidays.contains(&date)
+}
+
+/// Dates of the office-closing holidays from `start` to `end` inclusive
+pub(crate) async fn holiday_dates_between(
+    db: &Database,
+    start: NaiveDate,
+    end: NaiveDate,
+) -> Result<HashSet<NaiveDate>, String> {
+    let filter = doc! { "year": { "$gte": start.year(), "$lte": end.year() } };
+
+    Ok(find_holidays(db, filter)
+        .await?
+        .into_iter()
+        .filter(|h| h.holiday_type.closes_office())
+        .filter_map(|h| NaiveDate::from_ymd_opt(h.year, h.month as u32, h.day as u32))
+        .filter(|date| *date >= start && *date <= end)
+        .collect())
+}
+
+/// Count weekdays of the month within `days` that are not in `holiday_days`
+pub(crate) fn count_working_days(year: i32, month: u32, days: RangeInclusive<u32>, holiday_days: &HashSet<u32>) -> i32 {
+    days
+        .filter_map(|day| NaiveDate::from_ymd_opt(year, month, day))
+        .filter(|date| !is_weekend(*date) && !holiday_days.contains(&date.day()))
+        .count() as i32
+}
+
+/// Working day breakdown of a month using the holiday calendar
+pub(crate) async fn working_days_in_month(
+    db: &Database,
+    month: i32,
+    year: i32,
+) -> Result<WorkingDays, String> {
+    let calendar_days = days_in_month(year, month as u32)
+        .ok_or_else(|| format!("Invalid month: {}", month))?;
+
+    let holidays: Vec<Holiday> = find_holidays(db, doc! { "year": year, "month": month })
+        .await?
+        .into_iter()
+        .filter(|h| h.holiday_type.closes_office())
+        .filter(|h| NaiveDate::from_ymd_opt(year, month as u32, h.day as u32)
+            .map(|date| !is_weekend(date))
+            .unwrap_or(false))
+        .collect();
+
+    let holiday_days: HashSet<u32> = holidays.iter().map(|h| h.day as u32).collect();
+
+    let weekend_days = (1..=calendar_days)
+        .filter_map(|day| NaiveDate::from_ymd_opt(year, month as u32, day))
+        .filter(|date| is_weekend(*date))
+        .count() as i32;
+
+    Ok(WorkingDays {
+        month,
+        year,
+        calendar_days: calendar_days as i32,
+        weekend_days,
+        holidays,
+        working_days: count_working_days(year, month as u32, 1..=calendar_days, &holiday_days),
+    })
+}
+
+/// Working days of a month between two days of the month, inclusive
+pub(crate) fn working_days_between(working_days: &WorkingDays, first_day: i32, last_day: i32) -> i32 {
+    let holiday_days: HashSet<u32> = working_days.holidays.iter().map(|h| h.day as u32).collect();
+    let (first_day, last_day) = (first_day.max(1) as u32, last_day.max(0) as u32);
+    count_working_days(working_days.year, working_days.month as u32, first_day..=last_day, &holiday_days)
+}
+
+/// Parse the all-day events of an iCalendar file into holidays, one per day
+fn parse_ics(content: &str, default_type: HolidayType) -> Vec<Holiday> {
+    // Unfold continuation lines (RFC 5545 section 3.1)
+    let mut lines: Vec<String> = Vec::new();
+    for raw in content.lines() {
+        let raw = raw.trim_end_matches('\r');
+        if (raw.starts_with(' ') || raw.starts_with('\t')) && !lines.is_empty() {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(&raw[1..]);
+            }
+        } else {
+            lines.push(raw.to_string());
+        }
+    }
+
+    let mut holidays = Vec::new();
+    let mut in_event = false;
+    let mut start: Option<NaiveDate> = None;
+    let mut end: Option<NaiveDate> = None;
+    let mut summary = String::new();
+    let mut categories = String::new();
+
+    for line in &lines {
+        let (key, value) = match line.split_once(':') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        // Drop parameters such as DTSTART;VALUE=DATE
+        let name = key.split(';').next().unwrap_or("").to_uppercase();
+
+        match name.as_str() {
+            "BEGIN" if value.eq_ignore_ascii_case("VEVENT") => {
+                in_event = true;
+                start = None;
+                end = None;
+                summary.clear();
+                categories.clear();
+            },
+            "END" if value.eq_ignore_ascii_case("VEVENT") && in_event => {
+                in_event = false;
+                let first = match start {
+                    Some(date) => date,
+                    None => continue,
+                };
+                if summary.trim().is_empty() {
+                    continue;
+                }
+
+                let holiday_type = ics_holiday_type(&summary, &categories, default_type);
+                // DTEND is exclusive for all-day events
+                let last = end
+                    .and_then(|date| date.pred_opt())
+                    .filter(|date| *date >= first)
+                    .unwrap_or(first);
+
+                for date in first.iter_days().take_while(|d| *d <= last) {
+                    holidays.push(Holiday {
+                        id: None,
+                        date: format_date(&date),
+                        name: unescape_ics(summary.trim()),
+                        holiday_type,
+                        year: date.year(),
+                        month: date.month() as i32,
+                        day: date.day() as i32,
+                        notes: None,
+                        created_at: None,
+                        updated_at: None,
+                    });
+                }
+            },
+            "DTSTART" if in_event => start = parse_ics_date(value),
+            "DTEND" if in_event => end = parse_ics_date(value),
+            "SUMMARY" if in_event => summary = value.to_string(),
+            "CATEGORIES" if in_event => categories = value.to_string(),
+            _ => {},
+        }
+    }
+
+    holidays
+}
+
+/// Parse the date part of an iCalendar DATE or DATE-TIME value
+fn parse_ics_date(value: &str) -> Option<NaiveDate> {
+    let date_part = value.trim().get(0..8)?;
+    NaiveDate::parse_from_str(date_part, "%Y%m%d").ok()
+}
+
+fn ics_holiday_type(summary: &str, categories: &str, default_type: HolidayType) -> HolidayType {
+    let text = format!("{} {}", summary, categories).to_lowercase();
+    if text.contains("poya") {
+        HolidayType::Poya
+    } else if text.contains("mercantile") {
+        HolidayType::Mercantile
+    } else if text.contains("bank") {
+        HolidayType::Bank
+    } else if text.contains("public") {
+        HolidayType::Public
+    } else {
+        default_type
+    }
+}
+
+fn unescape_ics(value: &str) -> String {
+    value
+        .replace("\\,", ",")
+        .replace("\\;", ";")
+        .replace("\\n", " ")
+        .replace("\\N", " ")
+        .replace("\\\\", "\\")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn calendar(events: &str) -> String {
+        format!("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n{}END:VCALENDAR\r\n", events)
+    }
+
+    #[test]
+    fn parses_all_day_event() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250414\r\nDTEND;VALUE=DATE:20250415\r\nSUMMARY:Sinhala and Tamil New Year\\, Public Holiday\r\nEND:VEVENT\r\n"
+        );
+        let holidays = parse_ics(&content, HolidayType::OfficeClosure);
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(holidays[0].date, "14-04-2025");
+        assert_eq!(holidays[0].name, "Sinhala and Tamil New Year, Public Holiday");
+        assert_eq!(holidays[0].holiday_type, HolidayType::Public);
+        assert_eq!((holidays[0].year, holidays[0].month, holidays[0].day), (2025, 4, 14));
+    }
+
+    #[test]
+    fn parses_date_time_start() {
+        let content = calendar(
+            "BEGIN:VEVENT\nDTSTART:20251105T000000Z\nSUMMARY:Il Full Moon Poya Day\nEND:VEVENT\n"
+        );
+        let holidays = parse_ics(&content, HolidayType::OfficeClosure);
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(holidays[0].date, "05-11-2025");
+        assert_eq!(holidays[0].holiday_type, HolidayType::Poya);
+    }
+
+    #[test]
+    fn unfolds_continuation_lines() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20251225\r\nSUMMARY:Christmas \r\n Day\r\nCATEGORIES:Bank\r\n\tHoliday\r\nEND:VEVENT\r\n"
+        );
+        let holidays = parse_ics(&content, HolidayType::OfficeClosure);
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(holidays[0].name, "Christmas Day");
+        assert_eq!(holidays[0].holiday_type, HolidayType::Bank);
+    }
+
+    #[test]
+    fn treats_dtend_as_exclusive() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250413\r\nDTEND;VALUE=DATE:20250416\r\nSUMMARY:New Year Season\r\nEND:VEVENT\r\n"
+        );
+        let dates: Vec<String> = parse_ics(&content, HolidayType::OfficeClosure)
+            .into_iter()
+            .map(|h| h.date)
+            .collect();
+        assert_eq!(dates, vec!["13-04-2025", "14-04-2025", "15-04-2025"]);
+    }
+
+    #[test]
+    fn ignores_dtend_before_start() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250501\r\nDTEND;VALUE=DATE:20250501\r\nSUMMARY:May Day\r\nEND:VEVENT\r\n"
+        );
+        let holidays = parse_ics(&content, HolidayType::Mercantile);
+        assert_eq!(holidays.len(), 1);
+        assert_eq!(holidays[0].date, "01-05-2025");
+        assert_eq!(holidays[0].holiday_type, HolidayType::Mercantile);
+    }
+
+    #[test]
+    fn skips_events_without_start_or_summary() {
+        let content = calendar(
+            "BEGIN:VEVENT\r\nSUMMARY:No date\r\nEND:VEVENT\r\nBEGIN:VEVENT\r\nDTSTART;VALUE=DATE:20250601\r\nEND:VEVENT\r\n"
+        );
+        assert!(parse_ics(&content, HolidayType::OfficeClosure).is_empty());
+    }
+}
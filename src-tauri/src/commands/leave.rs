@@ -0,0 +1,917 @@
+use crate::models::{employee::Employee, leave::*, dates::format_date};
+use crate::commands::attendance::{apply_leave_to_attendance, revert_leave_from_attendance, is_month_locked, month_locked_error};
+use crate::commands::holiday::{holiday_dates_between, is_working_day};
+use crate::commands::print::{generate_html_rollover_report, PrintOptions};
+use crate::database::{Collections, helpers::*};
+use crate::AppState;
+use mongodb::bson::{doc, oid::ObjectId, DateTime, RawDocumentBuf};
+use mongodb::options::{FindOptions, ReplaceOptions};
+use mongodb::Database;
+use tauri::State;
+use futures::stream::TryStreamExt;
+use chrono::{Datelike, NaiveDate};
+use std::collections::{HashMap, HashSet};
+
+/// Maximum vacation days carried into the next year unless a cap is given
+const DEFAULT_CARRY_FORWARD_CAP: f64 = 24.0;
+
+/// Get leave applications with optional employee and status filtering
+#[tauri::command]
+pub async fn get_leave_applications(
+    state: State<'_, AppState>,
+    employee_id: Option<String>,
+    status: Option<LeaveStatus>,
+) -> Result<Vec<LeaveApplication>, String> {
+    let db = &state.db;
+    let collection = db.collection::<LeaveApplication>(Collections::LEAVES);
+
+    let mut filter_doc = doc! {};
+
+    if let Some(emp_id) = employee_id {
+        filter_doc.insert("employeeId", emp_id);
+    }
+    if let Some(status) = status {
+        filter_doc.insert(
+            "status",
+            mongodb::bson::to_bson(&status)
+                .map_err(|e| format!("Failed to serialize leave status: {}", e))?,
+        );
+    }
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "createdAt": -1 })
+        .build();
+
+    let mut cursor = collection.find(filter_doc, find_options)
+        .await
+        .map_err(|e| format!("Failed to find leave applications: {}", e))?;
+
+    let mut applications = Vec::new();
+    while let Some(application) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave applications: {}", e))? {
+        applications.push(application);
+    }
+
+    Ok(applications)
+}
+
+/// Create a new leave application
+#[tauri::command]
+pub async fn create_leave_application(
+    state: State<'_, AppState>,
+    request: CreateLeaveRequest,
+) -> Result<LeaveResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<LeaveApplication>(Collections::LEAVES);
+    let employee_collection = db.collection::<Employee>(Collections::EMPLOYEES);
+
+    let new_application = request.leave_application;
+
+    // Validate the employee
+    let emp_filter = id_filter(&new_application.employee_id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let employee = match employee_collection.find_one(emp_filter, None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(leave_error("Employee not found")),
+    };
+
+    let mut application = LeaveApplication {
+        id: None,
+        employee_id: new_application.employee_id,
+        employee_number: employee.employee_number,
+        leave_type: new_application.leave_type,
+        start_date: new_application.start_date,
+        end_date: new_application.end_date,
+        total_days: 0.0,
+        is_half_day: new_application.is_half_day,
+        reason: new_application.reason,
+        status: LeaveStatus::Pending,
+        applied_date: format_date(&chrono::Local::now().date_naive()),
+        approved_by: None,
+        approved_date: None,
+        rejected_reason: None,
+        created_at: None,
+        updated_at: None,
+    };
+
+    // Validate the leave details
+    if application.is_half_day && matches!(application.date_range(), Some((start, end)) if start != end) {
+        return Ok(leave_error("Half-day leave must start and end on the same date"));
+    }
+    let holidays = application_holidays(db, &application).await?;
+    let total_days = match application.calculate_total_days(|d| is_working_day(d, &holidays)) {
+        Some(days) => days,
+        None => return Ok(leave_error("Invalid leave dates. Use dd-MM-yyyy and an end date on or after the start date")),
+    };
+    if total_days == 0.0 {
+        return Ok(leave_error("The leave dates fall on weekends or holidays only"));
+    }
+
+    if application.reason.trim().is_empty() {
+        return Ok(leave_error("A reason for leave is required"));
+    }
+
+    if let Some(conflict) = find_overlapping_application(db, &application).await? {
+        return Ok(leave_error(&format!(
+            "Leave already applied for {} to {}",
+            conflict.start_date, conflict.end_date
+        )));
+    }
+
+    application.id = Some(ObjectId::new());
+    application.total_days = total_days;
+    application.created_at = Some(DateTime::now());
+    application.updated_at = Some(DateTime::now());
+
+    match collection.insert_one(&application, None).await {
+        Ok(_) => Ok(LeaveResponse {
+            success: true,
+            data: Some(application),
+            message: Some("Leave application created successfully".to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(leave_error(&format!("Failed to create leave application: {}", e))),
+    }
+}
+
+/// Update the status of a leave application
+#[tauri::command]
+pub async fn update_leave_application(
+    state: State<'_, AppState>,
+    id: String,
+    request: UpdateLeaveRequest,
+) -> Result<LeaveResponse, String> {
+    change_leave_status(&state.db, &id, request).await
+}
+
+/// Approve a pending leave application
+#[tauri::command]
+pub async fn approve_leave_application(
+    state: State<'_, AppState>,
+    id: String,
+    approved_by: String,
+) -> Result<LeaveResponse, String> {
+    let request = UpdateLeaveRequest {
+        status: LeaveStatus::Approved,
+        approved_by: Some(approved_by),
+        rejected_reason: None,
+    };
+
+    change_leave_status(&state.db, &id, request).await
+}
+
+/// Reject a pending leave application, or revoke an approved one
+#[tauri::command]
+pub async fn reject_leave_application(
+    state: State<'_, AppState>,
+    id: String,
+    reason: String,
+) -> Result<LeaveResponse, String> {
+    let request = UpdateLeaveRequest {
+        status: LeaveStatus::Rejected,
+        approved_by: None,
+        rejected_reason: Some(reason),
+    };
+
+    change_leave_status(&state.db, &id, request).await
+}
+
+/// Cancel a pending or approved leave application, returning approved days to the balance
+#[tauri::command]
+pub async fn cancel_leave_application(
+    state: State<'_, AppState>,
+    id: String,
+    reason: Option<String>,
+) -> Result<LeaveResponse, String> {
+    let request = UpdateLeaveRequest {
+        status: LeaveStatus::Cancelled,
+        approved_by: None,
+        rejected_reason: reason,
+    };
+
+    change_leave_status(&state.db, &id, request).await
+}
+
+/// Get the leave balance of an employee for a year
+#[tauri::command]
+pub async fn get_leave_balance(
+    state: State<'_, AppState>,
+    employee_id: String,
+    year: i32,
+) -> Result<LeaveBalanceResponse, String> {
+    let db = &state.db;
+    let employee_collection = db.collection::<Employee>(Collections::EMPLOYEES);
+
+    let emp_filter = id_filter(&employee_id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let employee = match employee_collection.find_one(emp_filter, None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(LeaveBalanceResponse {
+            success: false,
+            data: None,
+            message: Some("Employee not found".to_string()),
+            error: None,
+        }),
+    };
+
+    let balances = compute_leave_balances(db, &employee_id, &employee, year).await?;
+
+    Ok(LeaveBalanceResponse {
+        success: true,
+        data: Some(EmployeeLeaveBalance {
+            employee_id,
+            employee_number: employee.employee_number,
+            year,
+            balances,
+        }),
+        message: None,
+        error: None,
+    })
+}
+
+/// Get all configured leave entitlements
+#[tauri::command]
+pub async fn get_leave_entitlements(
+    state: State<'_, AppState>,
+) -> Result<Vec<LeaveEntitlement>, String> {
+    let db = &state.db;
+    let collection = db.collection::<LeaveEntitlement>(Collections::LEAVE_ENTITLEMENTS);
+
+    let mut cursor = collection.find(doc! {}, None)
+        .await
+        .map_err(|e| format!("Failed to find leave entitlements: {}", e))?;
+
+    let mut entitlements = Vec::new();
+    while let Some(entitlement) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave entitlements: {}", e))? {
+        entitlements.push(entitlement);
+    }
+
+    Ok(entitlements)
+}
+
+/// Create or replace the entitlement for a leave type, designation and service
+#[tauri::command]
+pub async fn set_leave_entitlement(
+    state: State<'_, AppState>,
+    entitlement: LeaveEntitlement,
+) -> Result<LeaveEntitlementResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<LeaveEntitlement>(Collections::LEAVE_ENTITLEMENTS);
+
+    if entitlement.days < 0.0 {
+        return Ok(LeaveEntitlementResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some("Entitled days cannot be negative".to_string()),
+        });
+    }
+
+    let filter = doc! {
+        "leaveType": mongodb::bson::to_bson(&entitlement.leave_type)
+            .map_err(|e| format!("Failed to serialize leave type: {}", e))?,
+        "designation": mongodb::bson::to_bson(&entitlement.designation)
+            .map_err(|e| format!("Failed to serialize designation: {}", e))?,
+        "centralProvincial": mongodb::bson::to_bson(&entitlement.central_provincial)
+            .map_err(|e| format!("Failed to serialize service: {}", e))?
+    };
+
+    let existing = collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to check existing entitlement: {}", e))?;
+
+    let mut entitlement = entitlement;
+    entitlement.id = existing.as_ref().and_then(|e| e.id).or_else(|| Some(ObjectId::new()));
+    entitlement.created_at = existing.and_then(|e| e.created_at).or_else(|| Some(DateTime::now()));
+    entitlement.updated_at = Some(DateTime::now());
+
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    match collection.replace_one(doc! { "_id": entitlement.id }, &entitlement, options).await {
+        Ok(_) => Ok(LeaveEntitlementResponse {
+            success: true,
+            data: Some(entitlement),
+            message: Some("Leave entitlement saved successfully".to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(LeaveEntitlementResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Failed to save leave entitlement: {}", e)),
+        }),
+    }
+}
+
+/// Delete a configured leave entitlement, reverting to the default
+#[tauri::command]
+pub async fn delete_leave_entitlement(
+    state: State<'_, AppState>,
+    id: String,
+) -> Result<LeaveEntitlementResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<LeaveEntitlement>(Collections::LEAVE_ENTITLEMENTS);
+
+    let filter = id_filter(&id)
+        .map_err(|e| format!("Invalid entitlement ID: {}", e))?;
+
+    match collection.delete_one(filter, None).await {
+        Ok(result) => Ok(LeaveEntitlementResponse {
+            success: result.deleted_count > 0,
+            data: None,
+            message: Some(if result.deleted_count > 0 {
+                "Leave entitlement deleted successfully".to_string()
+            } else {
+                "Leave entitlement not found".to_string()
+            }),
+            error: None,
+        }),
+        Err(e) => Ok(LeaveEntitlementResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Failed to delete leave entitlement: {}", e)),
+        }),
+    }
+}
+
+/// Carry unused vacation leave into the next year and write a rollover report
+#[tauri::command]
+pub async fn run_leave_rollover(
+    state: State<'_, AppState>,
+    year: i32,
+    carry_forward_cap: Option<f64>,
+    options: PrintOptions,
+) -> Result<LeaveRolloverResponse, String> {
+    let db = &state.db;
+    let employee_collection = db.collection::<Employee>(Collections::EMPLOYEES);
+    let ledger_collection = db.collection::<LeaveLedgerEntry>(Collections::LEAVE_LEDGER);
+
+    let cap = carry_forward_cap.unwrap_or(DEFAULT_CARRY_FORWARD_CAP).max(0.0);
+    let next_year = year + 1;
+    let vacation = mongodb::bson::to_bson(&LeaveType::VacationLeave)
+        .map_err(|e| format!("Failed to serialize leave type: {}", e))?;
+
+    // Load the entitlement rules and the year's vacation ledger once for all employees
+    let entitlements = load_entitlements(db).await?;
+
+    let mut ledger_cursor = ledger_collection.find(doc! { "year": year, "leaveType": vacation.clone() }, None)
+        .await
+        .map_err(|e| format!("Failed to find leave ledger entries: {}", e))?;
+
+    let mut ledger_by_employee: HashMap<String, Vec<LeaveLedgerEntry>> = HashMap::new();
+    while let Some(entry) = ledger_cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave ledger entries: {}", e))? {
+        ledger_by_employee.entry(entry.employee_id.clone()).or_default().push(entry);
+    }
+
+    let find_options = FindOptions::builder()
+        .sort(doc! { "employeeNumber": 1 })
+        .build();
+
+    let mut cursor = employee_collection.clone_with_type::<RawDocumentBuf>().find(doc! { "archived": { "$ne": true } }, find_options)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut entries = Vec::new();
+    let mut opening_entries = Vec::new();
+
+    while let Some(employee) = next_readable::<Employee>(&mut cursor)
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        let employee_id = match employee.id.as_ref() {
+            Some(id) => object_id_to_string(id),
+            None => continue,
+        };
+
+        // Unused days come from the ledger only; pending applications do not reduce them
+        let balances = ledger_balances(
+            resolve_entitlements(&entitlements, &employee),
+            ledger_by_employee.get(&employee_id).into_iter().flatten(),
+        );
+        let unused = balances.iter()
+            .find(|b| b.leave_type == LeaveType::VacationLeave)
+            .map(|b| (b.entitled - b.taken).max(0.0))
+            .unwrap_or(0.0);
+
+        let carried_forward = unused.min(cap);
+
+        if carried_forward > 0.0 {
+            opening_entries.push(LeaveLedgerEntry {
+                id: Some(ObjectId::new()),
+                employee_id: employee_id.clone(),
+                year: next_year,
+                leave_type: LeaveType::VacationLeave,
+                entry_type: LedgerEntryType::Opening,
+                days: carried_forward,
+                leave_application_id: None,
+                description: Some(format!("Carried forward from {}", year)),
+                created_at: Some(DateTime::now()),
+            });
+        }
+
+        entries.push(LeaveRolloverEntry {
+            employee_id,
+            employee_number: employee.employee_number,
+            full_name: employee.full_name,
+            unused,
+            carried_forward,
+            lapsed: unused - carried_forward,
+        });
+    }
+
+    // Replace any opening balances from an earlier run so the rollover can be repeated
+    ledger_collection.delete_many(
+        doc! { "year": next_year, "entryType": "opening", "leaveType": vacation },
+        None
+    ).await
+        .map_err(|e| format!("Failed to clear previous opening balances: {}", e))?;
+
+    if !opening_entries.is_empty() {
+        ledger_collection.insert_many(opening_entries, None)
+            .await
+            .map_err(|e| format!("Failed to write opening balances: {}", e))?;
+    }
+
+    let report = LeaveRolloverReport {
+        from_year: year,
+        to_year: next_year,
+        carry_forward_cap: cap,
+        total_carried_forward: entries.iter().map(|e| e.carried_forward).sum(),
+        total_lapsed: entries.iter().map(|e| e.lapsed).sum(),
+        entries,
+    };
+
+    let print_response = generate_html_rollover_report(&report, options).await?;
+
+    Ok(LeaveRolloverResponse {
+        success: true,
+        data: Some(report),
+        file_path: print_response.file_path,
+        message: Some(format!("Leave rolled over from {} to {}", year, next_year)),
+        error: print_response.error,
+    })
+}
+
+// Helper functions for leave applications
+
+fn leave_error(error: &str) -> LeaveResponse {
+    LeaveResponse {
+        success: false,
+        data: None,
+        message: None,
+        error: Some(error.to_string()),
+    }
+}
+
+/// Move a leave application to a new status, validating the transition
+async fn change_leave_status(
+    db: &Database,
+    id: &str,
+    request: UpdateLeaveRequest,
+) -> Result<LeaveResponse, String> {
+    let collection = db.collection::<LeaveApplication>(Collections::LEAVES);
+
+    let filter = id_filter(id)
+        .map_err(|e| format!("Invalid leave application ID: {}", e))?;
+
+    let mut application = match collection.find_one(filter.clone(), None)
+        .await
+        .map_err(|e| format!("Failed to find leave application: {}", e))? {
+        Some(application) => application,
+        None => return Ok(LeaveResponse {
+            success: false,
+            data: None,
+            message: Some("Leave application not found".to_string()),
+            error: None,
+        }),
+    };
+
+    if !application.status.can_transition_to(request.status) {
+        return Ok(leave_error(&format!(
+            "Cannot change a {:?} leave application to {:?}",
+            application.status, request.status
+        )));
+    }
+
+    match request.status {
+        LeaveStatus::Approved => {
+            let approved_by = match request.approved_by.filter(|a| !a.trim().is_empty()) {
+                Some(approved_by) => approved_by,
+                None => return Ok(leave_error("Approver is required to approve leave")),
+            };
+            application.approved_by = Some(approved_by);
+            application.approved_date = Some(format_date(&chrono::Local::now().date_naive()));
+        },
+        LeaveStatus::Rejected => {
+            let reason = match request.rejected_reason.filter(|r| !r.trim().is_empty()) {
+                Some(reason) => reason,
+                None => return Ok(leave_error("A reason is required to reject leave")),
+            };
+            application.rejected_reason = Some(reason);
+        },
+        LeaveStatus::Cancelled => {
+            if let Some(reason) = request.rejected_reason {
+                application.rejected_reason = Some(reason);
+            }
+        },
+        LeaveStatus::Pending => {},
+    }
+
+    // Approving or revoking approved leave changes attendance, which locked months refuse
+    let changes_attendance = request.status == LeaveStatus::Approved
+        || application.status == LeaveStatus::Approved;
+    if changes_attendance {
+        if let Some((start, end)) = application.date_range() {
+            let mut months: Vec<(i32, i32)> = start.iter_days()
+                .take_while(|d| *d <= end)
+                .map(|d| (d.month() as i32, d.year()))
+                .collect();
+            months.dedup();
+            for (month, year) in months {
+                if is_month_locked(db, month, year).await? {
+                    return Ok(leave_error(&month_locked_error(month, year)));
+                }
+            }
+        }
+    }
+
+    // Write the ledger before the status so a failure leaves the application unchanged
+    let ledger_entry_ids = match (application.status, request.status) {
+        (_, LeaveStatus::Approved) => {
+            let holidays = application_holidays(db, &application).await?;
+            let days_by_year = application.days_by_year(|d| is_working_day(d, &holidays));
+
+            if let Some(error) = check_leave_balance(db, &application, &days_by_year).await? {
+                return Ok(leave_error(&error));
+            }
+
+            record_ledger_entries(db, &application, LedgerEntryType::Debit, days_by_year).await?
+        },
+        (LeaveStatus::Approved, LeaveStatus::Cancelled | LeaveStatus::Rejected) => {
+            // Return exactly what was debited, even if the holidays changed since
+            let days_by_year = debited_days_by_year(db, &application).await?;
+            record_ledger_entries(db, &application, LedgerEntryType::Credit, days_by_year).await?
+        },
+        _ => Vec::new(),
+    };
+
+    let previous_status = application.status;
+    application.status = request.status;
+    application.updated_at = Some(DateTime::now());
+
+    let update_doc = doc! {
+        "$set": {
+            "status": mongodb::bson::to_bson(&application.status)
+                .map_err(|e| format!("Failed to serialize leave status: {}", e))?,
+            "approvedBy": application.approved_by.clone(),
+            "approvedDate": application.approved_date.clone(),
+            "rejectedReason": application.rejected_reason.clone(),
+            "updatedAt": DateTime::now()
+        }
+    };
+
+    // The status is only changed if it is still the one the ledger was written for
+    let mut filter = filter;
+    filter.insert("status", mongodb::bson::to_bson(&previous_status)
+        .map_err(|e| format!("Failed to serialize leave status: {}", e))?);
+
+    let updated = collection.update_one(filter, update_doc, None).await;
+    if !matches!(&updated, Ok(result) if result.matched_count == 1) {
+        remove_ledger_entries(db, &ledger_entry_ids).await?;
+        return Ok(leave_error(&match updated {
+            Err(e) => format!("Failed to update leave application: {}", e),
+            Ok(_) => "The leave application was changed by someone else. Reload and try again".to_string(),
+        }));
+    }
+
+    // Keep attendance in step with the application
+    match (previous_status, application.status) {
+        (_, LeaveStatus::Approved) => {
+            apply_leave_to_attendance(db, &application).await?;
+        },
+        (LeaveStatus::Approved, LeaveStatus::Cancelled | LeaveStatus::Rejected) => {
+            if let Some(application_id) = application.id.as_ref() {
+                revert_leave_from_attendance(db, &object_id_to_string(application_id)).await?;
+            }
+        },
+        _ => {},
+    }
+
+    Ok(LeaveResponse {
+        success: true,
+        data: Some(application),
+        message: Some("Leave application updated successfully".to_string()),
+        error: None,
+    })
+}
+
+/// Office-closing holidays falling within the dates of the application
+async fn application_holidays(
+    db: &Database,
+    application: &LeaveApplication,
+) -> Result<HashSet<NaiveDate>, String> {
+    match application.date_range() {
+        Some((start, end)) => holiday_dates_between(db, start, end).await,
+        None => Ok(HashSet::new()),
+    }
+}
+
+/// Days still debited from the balance for the application, per calendar year
+async fn debited_days_by_year(
+    db: &Database,
+    application: &LeaveApplication,
+) -> Result<Vec<(i32, f64)>, String> {
+    let collection = db.collection::<LeaveLedgerEntry>(Collections::LEAVE_LEDGER);
+
+    let application_id = match application.id.as_ref() {
+        Some(id) => object_id_to_string(id),
+        None => return Ok(Vec::new()),
+    };
+
+    let mut cursor = collection.find(doc! { "leaveApplicationId": &application_id }, None)
+        .await
+        .map_err(|e| format!("Failed to find leave ledger entries: {}", e))?;
+
+    let mut result: Vec<(i32, f64)> = Vec::new();
+    while let Some(entry) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave ledger entries: {}", e))? {
+        let days = match entry.entry_type {
+            LedgerEntryType::Debit => entry.days,
+            LedgerEntryType::Credit => -entry.days,
+            LedgerEntryType::Opening => continue,
+        };
+        match result.iter_mut().find(|(year, _)| *year == entry.year) {
+            Some((_, total)) => *total += days,
+            None => result.push((entry.year, days)),
+        }
+    }
+
+    result.retain(|(_, days)| *days > 0.0);
+    result.sort_by_key(|(year, _)| *year);
+    Ok(result)
+}
+
+/// Check that the employee has enough leave left each year to approve the application.
+/// Returns the reason it cannot be approved.
+async fn check_leave_balance(
+    db: &Database,
+    application: &LeaveApplication,
+    days_by_year: &[(i32, f64)],
+) -> Result<Option<String>, String> {
+    if !application.leave_type.is_limited() {
+        return Ok(None);
+    }
+
+    let employee_collection = db.collection::<Employee>(Collections::EMPLOYEES);
+    let emp_filter = id_filter(&application.employee_id)
+        .map_err(|e| format!("Invalid employee ID: {}", e))?;
+
+    let employee = match employee_collection.find_one(emp_filter, None)
+        .await
+        .map_err(|e| format!("Failed to find employee: {}", e))? {
+        Some(employee) => employee,
+        None => return Ok(Some("Employee not found".to_string())),
+    };
+
+    for (year, days) in days_by_year {
+        let balances = compute_leave_balances(db, &application.employee_id, &employee, *year).await?;
+        // Other pending applications are not yet taken, so only approved leave counts
+        let available = balances.iter()
+            .find(|b| b.leave_type == application.leave_type)
+            .map(|b| b.entitled - b.taken)
+            .unwrap_or(0.0);
+
+        if *days > available {
+            return Ok(Some(format!(
+                "Only {} days of this leave remain for {}, {} requested",
+                available.max(0.0), year, days
+            )));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Write one ledger entry per calendar year covered by the application,
+/// returning the IDs of the entries written
+async fn record_ledger_entries(
+    db: &Database,
+    application: &LeaveApplication,
+    entry_type: LedgerEntryType,
+    days_by_year: Vec<(i32, f64)>,
+) -> Result<Vec<ObjectId>, String> {
+    let collection = db.collection::<LeaveLedgerEntry>(Collections::LEAVE_LEDGER);
+
+    let application_id = application.id.as_ref().map(object_id_to_string);
+
+    let entries: Vec<LeaveLedgerEntry> = days_by_year
+        .into_iter()
+        .map(|(year, days)| LeaveLedgerEntry {
+            id: Some(ObjectId::new()),
+            employee_id: application.employee_id.clone(),
+            year,
+            leave_type: application.leave_type,
+            entry_type,
+            days,
+            leave_application_id: application_id.clone(),
+            description: Some(format!("{} to {}", application.start_date, application.end_date)),
+            created_at: Some(DateTime::now()),
+        })
+        .collect();
+
+    if entries.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let entry_ids: Vec<ObjectId> = entries.iter().filter_map(|entry| entry.id).collect();
+
+    collection.insert_many(entries, None)
+        .await
+        .map_err(|e| format!("Failed to record leave ledger entries: {}", e))?;
+
+    Ok(entry_ids)
+}
+
+/// Remove ledger entries written for a status change that did not go through
+async fn remove_ledger_entries(db: &Database, entry_ids: &[ObjectId]) -> Result<(), String> {
+    if entry_ids.is_empty() {
+        return Ok(());
+    }
+
+    let collection = db.collection::<LeaveLedgerEntry>(Collections::LEAVE_LEDGER);
+
+    collection.delete_many(doc! { "_id": { "$in": entry_ids } }, None)
+        .await
+        .map_err(|e| format!("Failed to remove leave ledger entries: {}", e))?;
+
+    Ok(())
+}
+
+/// Load every configured leave entitlement rule
+async fn load_entitlements(db: &Database) -> Result<Vec<LeaveEntitlement>, String> {
+    let collection = db.collection::<LeaveEntitlement>(Collections::LEAVE_ENTITLEMENTS);
+
+    let mut cursor = collection.find(doc! {}, None)
+        .await
+        .map_err(|e| format!("Failed to find leave entitlements: {}", e))?;
+
+    let mut entitlements = Vec::new();
+    while let Some(entitlement) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave entitlements: {}", e))? {
+        entitlements.push(entitlement);
+    }
+
+    Ok(entitlements)
+}
+
+/// Resolve the yearly entitlement for each leave type for the given employee
+fn resolve_entitlements(
+    entitlements: &[LeaveEntitlement],
+    employee: &Employee,
+) -> Vec<(LeaveType, f64)> {
+    LeaveType::ALL.iter().map(|leave_type| {
+        let days = entitlements.iter()
+            .filter(|e| e.leave_type == *leave_type)
+            .filter_map(|e| {
+                e.specificity(employee.designation, employee.central_provincial)
+                    .map(|score| (score, e.days))
+            })
+            .max_by_key(|(score, _)| *score)
+            .map(|(_, days)| days)
+            .unwrap_or_else(|| leave_type.default_entitlement());
+        (*leave_type, days)
+    }).collect()
+}
+
+/// Build the entitled and taken days per leave type from the entitlements and ledger entries
+fn ledger_balances<'a, I>(entitlements: Vec<(LeaveType, f64)>, entries: I) -> Vec<LeaveBalance>
+where
+    I: IntoIterator<Item = &'a LeaveLedgerEntry>,
+{
+    let mut balances: Vec<LeaveBalance> = entitlements
+        .into_iter()
+        .map(|(leave_type, entitled)| LeaveBalance {
+            leave_type,
+            entitled,
+            taken: 0.0,
+            pending: 0.0,
+            remaining: 0.0,
+        })
+        .collect();
+
+    for entry in entries {
+        if let Some(balance) = balances.iter_mut().find(|b| b.leave_type == entry.leave_type) {
+            match entry.entry_type {
+                LedgerEntryType::Opening => balance.entitled += entry.days,
+                LedgerEntryType::Debit => balance.taken += entry.days,
+                LedgerEntryType::Credit => balance.taken -= entry.days,
+            }
+        }
+    }
+
+    balances
+}
+
+/// Compute the entitled, taken, pending and remaining days per leave type for a year
+pub(crate) async fn compute_leave_balances(
+    db: &Database,
+    employee_id: &str,
+    employee: &Employee,
+    year: i32,
+) -> Result<Vec<LeaveBalance>, String> {
+    let ledger_collection = db.collection::<LeaveLedgerEntry>(Collections::LEAVE_LEDGER);
+    let leave_collection = db.collection::<LeaveApplication>(Collections::LEAVES);
+
+    let entitlements = load_entitlements(db).await?;
+
+    // Apply ledger entries for the year
+    let mut cursor = ledger_collection.find(doc! { "employeeId": employee_id, "year": year }, None)
+        .await
+        .map_err(|e| format!("Failed to find leave ledger entries: {}", e))?;
+
+    let mut ledger_entries = Vec::new();
+    while let Some(entry) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave ledger entries: {}", e))? {
+        ledger_entries.push(entry);
+    }
+
+    let mut balances = ledger_balances(resolve_entitlements(&entitlements, employee), &ledger_entries);
+
+    // Pending applications falling in the year
+    let year_start = NaiveDate::from_ymd_opt(year, 1, 1)
+        .ok_or_else(|| format!("Invalid year: {}", year))?;
+    let year_end = NaiveDate::from_ymd_opt(year, 12, 31)
+        .ok_or_else(|| format!("Invalid year: {}", year))?;
+    let holidays = holiday_dates_between(db, year_start, year_end).await?;
+
+    let mut cursor = leave_collection.find(doc! { "employeeId": employee_id, "status": "pending" }, None)
+        .await
+        .map_err(|e| format!("Failed to find leave applications: {}", e))?;
+
+    while let Some(application) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave applications: {}", e))? {
+        let days: f64 = application.days_by_year(|d| is_working_day(d, &holidays))
+            .into_iter()
+            .filter(|(y, _)| *y == year)
+            .map(|(_, days)| days)
+            .sum();
+        if let Some(balance) = balances.iter_mut().find(|b| b.leave_type == application.leave_type) {
+            balance.pending += days;
+        }
+    }
+
+    for balance in &mut balances {
+        balance.remaining = balance.entitled - balance.taken - balance.pending;
+    }
+
+    Ok(balances)
+}
+
+/// Find a pending or approved application of the same employee that overlaps the given dates
+async fn find_overlapping_application(
+    db: &Database,
+    application: &LeaveApplication,
+) -> Result<Option<LeaveApplication>, String> {
+    let collection = db.collection::<LeaveApplication>(Collections::LEAVES);
+
+    let (start, end) = match application.date_range() {
+        Some(range) => range,
+        None => return Ok(None),
+    };
+
+    let filter = doc! {
+        "employeeId": &application.employee_id,
+        "status": { "$in": ["pending", "approved"] }
+    };
+
+    let mut cursor = collection.find(filter, None)
+        .await
+        .map_err(|e| format!("Failed to find leave applications: {}", e))?;
+
+    while let Some(existing) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate leave applications: {}", e))? {
+        if let Some((existing_start, existing_end)) = existing.date_range() {
+            if existing_start <= end && start <= existing_end {
+                return Ok(Some(existing));
+            }
+        }
+    }
+
+    Ok(None)
+}
@@ -1,18 +1,51 @@
 pub mod employee;
 pub mod attendance;
+pub mod leave;
+pub mod holiday;
+pub mod settings;
+pub mod biometric;
+pub mod anomaly;
+pub mod dashboard;
+pub mod notification;
 pub mod print;
 
 // Use specific exports instead of glob exports to avoid naming conflicts
 pub use employee::{
     get_employees, get_employee_by_id, create_employee, update_employee,
-    delete_employee, search_employees
+    delete_employee, search_employees, get_orphaned_records, cleanup_orphaned_records,
+    archive_employee, restore_employee, change_employment_status, get_employment_status_history,
+    migrate_employee_dates, migrate_employee_statuses, get_duplicate_employees
 };
 
 pub use attendance::{
     get_attendance_records, create_attendance_record, update_attendance_record,
-    delete_attendance_record, get_monthly_summary, backup_monthly_data, clear_monthly_data
+    delete_attendance_record, get_monthly_summary, backup_monthly_data, clear_monthly_data,
+    mark_attendance_bulk, lock_month, unlock_month, get_attendance_locks, get_yearly_summary,
+    get_employee_attendance_history, resync_employee_numbers
 };
 
+pub use leave::{
+    get_leave_applications, create_leave_application, update_leave_application,
+    approve_leave_application, reject_leave_application, cancel_leave_application,
+    get_leave_balance, get_leave_entitlements, set_leave_entitlement, delete_leave_entitlement,
+    run_leave_rollover
+};
+
+pub use holiday::{
+    get_holidays, create_holiday, update_holiday, delete_holiday, import_holidays_ics,
+    get_working_days
+};
+
+pub use settings::{
+    get_attendance_settings, update_attendance_settings, get_retirement_settings, update_retirement_settings
+};
+
+pub use biometric::import_biometric_log;
+
+pub use anomaly::detect_attendance_anomalies;
+pub use dashboard::get_upcoming_events;
+pub use notification::{get_deadline_alerts, dismiss_deadline_alert, snooze_deadline_alert};
+
 pub use print::{
     generate_employee_report, generate_bulk_report, generate_attendance_report,
     export_to_excel, export_to_csv,
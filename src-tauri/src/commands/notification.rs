@@ -0,0 +1,228 @@
+use crate::models::{notification::*, dashboard::UpcomingEventType};
+use crate::commands::dashboard::find_upcoming_events;
+use crate::models::dates::from_bson_date;
+use crate::database::Collections;
+use crate::AppState;
+use mongodb::bson::{doc, DateTime};
+use mongodb::options::UpdateOptions;
+use mongodb::Database;
+use tauri::{AppHandle, Manager, State};
+use futures::stream::TryStreamExt;
+use std::collections::HashMap;
+
+/// Name of the Tauri event carrying newly raised deadline alerts
+pub const DEADLINE_ALERTS_EVENT: &str = "hr-deadline-alerts";
+
+/// How often the scheduler looks for deadlines
+const CHECK_INTERVAL_HOURS: u64 = 6;
+
+/// Days ahead a deadline is alerted
+const ALERT_WINDOW_DAYS: i64 = 30;
+
+/// Deadlines raised as alerts. The rest are only shown on the dashboard.
+const ALERTED_EVENTS: [UpcomingEventType; 3] = [
+    UpcomingEventType::Retirement,
+    UpcomingEventType::Increment,
+    UpcomingEventType::ServiceConfirmation,
+];
+
+/// Get the deadline alerts that are neither dismissed nor snoozed
+#[tauri::command]
+pub async fn get_deadline_alerts(
+    state: State<'_, AppState>,
+) -> Result<Vec<DeadlineAlert>, String> {
+    find_deadline_alerts(&state.db).await
+}
+
+/// Dismiss a deadline alert so it is not raised again
+#[tauri::command]
+pub async fn dismiss_deadline_alert(
+    state: State<'_, AppState>,
+    key: String,
+) -> Result<NotificationResponse, String> {
+    if !is_upcoming_alert(&state.db, &key).await? {
+        return Ok(unknown_alert_response(&key));
+    }
+
+    let update_doc = doc! {
+        "$set": { "dismissed": true, "updatedAt": DateTime::now() },
+        "$unset": { "snoozedUntil": "" }
+    };
+
+    set_notification_state(&state.db, &key, update_doc).await?;
+
+    Ok(NotificationResponse {
+        success: true,
+        message: Some("Alert dismissed".to_string()),
+        error: None,
+    })
+}
+
+/// Snooze a deadline alert, raising it again after `days` days
+#[tauri::command]
+pub async fn snooze_deadline_alert(
+    state: State<'_, AppState>,
+    key: String,
+    days: i64,
+) -> Result<NotificationResponse, String> {
+    if days < 1 {
+        return Ok(NotificationResponse {
+            success: false,
+            message: None,
+            error: Some("Snooze for at least one day".to_string()),
+        });
+    }
+
+    if !is_upcoming_alert(&state.db, &key).await? {
+        return Ok(unknown_alert_response(&key));
+    }
+
+    let snoozed_until = DateTime::from_millis(
+        DateTime::now().timestamp_millis() + days * 24 * 60 * 60 * 1000
+    );
+    let update_doc = doc! {
+        "$set": { "dismissed": false, "snoozedUntil": snoozed_until, "updatedAt": DateTime::now() },
+        "$unset": { "notifiedAt": "" }
+    };
+
+    set_notification_state(&state.db, &key, update_doc).await?;
+
+    Ok(NotificationResponse {
+        success: true,
+        message: Some(format!("Alert snoozed for {} days", days)),
+        error: None,
+    })
+}
+
+/// Raise notifications for new deadline alerts until the application exits
+pub async fn run_notification_scheduler(handle: AppHandle, db: Database) {
+    loop {
+        if let Err(e) = notify_deadlines(&handle, &db).await {
+            eprintln!("Failed to check HR deadlines: {}", e);
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(CHECK_INTERVAL_HOURS * 60 * 60)).await;
+    }
+}
+
+// Helper functions for deadline alerts
+
+/// Show a native notification and emit an event for the alerts not yet raised,
+/// then record them as raised so they are not repeated on the next launch
+async fn notify_deadlines(handle: &AppHandle, db: &Database) -> Result<(), String> {
+    let alerts: Vec<DeadlineAlert> = find_deadline_alerts(db).await?
+        .into_iter()
+        .filter(|alert| alert.notified_at.is_none())
+        .collect();
+
+    if alerts.is_empty() {
+        return Ok(());
+    }
+
+    let title = match alerts.len() {
+        1 => "HR deadline".to_string(),
+        count => format!("{} HR deadlines", count),
+    };
+    let mut lines: Vec<String> = alerts.iter()
+        .take(3)
+        .map(|alert| format!(
+            "{} {}: {} on {}",
+            alert.event.employee_number,
+            alert.event.full_name,
+            alert.event.description,
+            alert.event.date
+        ))
+        .collect();
+    if alerts.len() > lines.len() {
+        lines.push(format!("and {} more", alerts.len() - lines.len()));
+    }
+
+    let identifier = handle.config().tauri.bundle.identifier.clone();
+    if let Err(e) = tauri::api::notification::Notification::new(identifier)
+        .title(title)
+        .body(lines.join("\n"))
+        .show() {
+        eprintln!("Failed to show notification: {}", e);
+    }
+
+    handle.emit_all(DEADLINE_ALERTS_EVENT, alerts.clone())
+        .map_err(|e| format!("Failed to emit deadline alerts: {}", e))?;
+
+    for alert in &alerts {
+        let update_doc = doc! {
+            "$set": { "notifiedAt": DateTime::now(), "updatedAt": DateTime::now() }
+        };
+        set_notification_state(db, &alert.key, update_doc).await?;
+    }
+
+    Ok(())
+}
+
+/// Upcoming alerted deadlines, leaving out dismissed alerts and those still snoozed
+async fn find_deadline_alerts(db: &Database) -> Result<Vec<DeadlineAlert>, String> {
+    let collection = db.collection::<NotificationState>(Collections::NOTIFICATION_STATE);
+
+    let alerts = upcoming_alerts(db).await?;
+
+    let keys: Vec<&String> = alerts.iter().map(|alert| &alert.key).collect();
+    let mut cursor = collection.find(doc! { "_id": { "$in": keys } }, None)
+        .await
+        .map_err(|e| format!("Failed to find notification state: {}", e))?;
+
+    let mut states = HashMap::new();
+    while let Some(state) = cursor.try_next()
+        .await
+        .map_err(|e| format!("Failed to iterate notification state: {}", e))? {
+        states.insert(state.key.clone(), state);
+    }
+
+    let now = DateTime::now();
+    Ok(alerts.into_iter()
+        .filter_map(|mut alert| {
+            if let Some(state) = states.get(&alert.key) {
+                if state.dismissed || state.snoozed_until.map(|until| until > now).unwrap_or(false) {
+                    return None;
+                }
+                alert.notified_at = state.notified_at.as_ref().and_then(from_bson_date);
+            }
+            Some(alert)
+        })
+        .collect())
+}
+
+/// Every alert within the alert window, whether or not it was dismissed or snoozed
+async fn upcoming_alerts(db: &Database) -> Result<Vec<DeadlineAlert>, String> {
+    Ok(find_upcoming_events(db, ALERT_WINDOW_DAYS).await?
+        .events
+        .into_iter()
+        .filter(|event| ALERTED_EVENTS.contains(&event.event_type))
+        .map(|event| DeadlineAlert {
+            key: DeadlineAlert::key(&event),
+            event,
+            notified_at: None,
+        })
+        .collect())
+}
+
+/// Whether the key belongs to a current alert, so no state is kept for unknown keys
+async fn is_upcoming_alert(db: &Database, key: &str) -> Result<bool, String> {
+    Ok(upcoming_alerts(db).await?.iter().any(|alert| alert.key == key))
+}
+
+fn unknown_alert_response(key: &str) -> NotificationResponse {
+    NotificationResponse {
+        success: false,
+        message: None,
+        error: Some(format!("No upcoming deadline alert '{}'", key)),
+    }
+}
+
+async fn set_notification_state(db: &Database, key: &str, update_doc: mongodb::bson::Document) -> Result<(), String> {
+    let collection = db.collection::<NotificationState>(Collections::NOTIFICATION_STATE);
+    let options = UpdateOptions::builder().upsert(true).build();
+
+    collection.update_one(doc! { "_id": key }, update_doc, options)
+        .await
+        .map_err(|e| format!("Failed to update notification state: {}", e))?;
+
+    Ok(())
+}
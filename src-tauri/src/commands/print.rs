@@ -1,7 +1,9 @@
-use crate::models::{employee::Employee, attendance::*};
+use crate::models::{employee::Employee, attendance::*, anomaly::{AnomalyReport, AnomalyType}, leave::LeaveRolloverReport, holiday::WorkingDays, dates::format_date};
+use crate::commands::holiday::working_days_in_month;
+use crate::commands::settings::{load_attendance_settings, load_retirement_settings};
 use crate::database::{Collections, helpers::*};
 use crate::AppState;
-use mongodb::bson::{doc};
+use mongodb::bson::{doc, RawDocumentBuf};
 use tauri::State;
 use futures::stream::TryStreamExt;
 use serde::{Deserialize, Serialize};
@@ -49,7 +51,9 @@ pub async fn generate_employee_report(
         .map_err(|e| format!("Failed to find employee: {}", e))?;
 
     match employee {
-        Some(emp) => {
+        Some(mut emp) => {
+            let retirement_settings = load_retirement_settings(db).await?;
+            emp.derive_dates(&retirement_settings, chrono::Local::now().date_naive());
             if options.format == "html" {
                 generate_html_employee_report(emp, options).await
             } else {
@@ -91,14 +95,18 @@ pub async fn generate_bulk_report(
     let filter = doc! { "_id": { "$in": object_ids } };
 
     // Find employees
-    let mut cursor = collection.find(filter, None)
+    let mut cursor = collection.clone_with_type::<RawDocumentBuf>().find(filter, None)
         .await
         .map_err(|e| format!("Failed to find employees: {}", e))?;
 
+    let retirement_settings = load_retirement_settings(db).await?;
+    let today = chrono::Local::now().date_naive();
+
     let mut employees = Vec::new();
-    while let Some(employee) = cursor.try_next()
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
         .await
         .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        employee.derive_dates(&retirement_settings, today);
         employees.push(employee);
     }
 
@@ -147,7 +155,9 @@ pub async fn generate_attendance_report(
     }
 
     if options.format == "html" {
-        generate_html_attendance_report(attendance_data, month, year, options).await
+        let working_days = working_days_in_month(db, month, year).await?;
+        let settings = load_attendance_settings(db).await?;
+        generate_html_attendance_report(attendance_data, &working_days, settings.short_leaves_per_month, options).await
     } else {
         generate_pdf_attendance_report(attendance_data, month, year, options).await
     }
@@ -349,9 +359,9 @@ async fn generate_html_employee_report(
         employee.mobile_number,
         employee.email_address,
         employee.nic_number,
-        employee.date_of_birth,
+        format_date(&employee.date_of_birth),
         employee.age,
-        employee.first_appointment_date,
+        format_date(&employee.first_appointment_date),
         employee.salary_code
     );
 
@@ -413,7 +423,7 @@ async fn generate_html_bulk_report(
             emp.gender,
             emp.mobile_number,
             emp.nic_number,
-            emp.date_of_birth,
+            format_date(&emp.date_of_birth),
             emp.age,
             emp.salary_code,
             emp.central_provincial,
@@ -504,16 +514,126 @@ async fn generate_pdf_bulk_report(
 }
 
 async fn generate_html_attendance_report(
-    _attendance_data: Vec<(Employee, AttendanceRecord)>,
-    _month: i32,
-    _year: i32,
-    _options: PrintOptions,
+    attendance_data: Vec<(Employee, AttendanceRecord)>,
+    working_days: &WorkingDays,
+    short_leaves_per_month: i32,
+    options: PrintOptions,
 ) -> Result<PrintResponse, String> {
+    let downloads_dir = tauri::api::path::download_dir()
+        .ok_or_else(|| "Failed to get downloads directory".to_string())?;
+
+    let file_path = downloads_dir.join(&options.filename);
+
+    let table_rows: String = attendance_data.iter().map(|(emp, record)| {
+        let counts = AttendanceStatus::count(&record.records);
+        let count = |status: AttendanceStatus| counts.get(&status).copied().unwrap_or(0);
+        let totals = record.totals(working_days.working_days, short_leaves_per_month);
+
+        format!(
+            r#"
+            <tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{:.1}</td>
+                <td>{:.1}%</td>
+            </tr>
+            "#,
+            emp.employee_number,
+            emp.full_name,
+            count(AttendanceStatus::Present),
+            count(AttendanceStatus::Absent),
+            count(AttendanceStatus::HalfDay),
+            count(AttendanceStatus::OfficialDuty),
+            totals.leave_days,
+            count(AttendanceStatus::NoPay),
+            totals.attended_days,
+            totals.attendance_percentage
+        )
+    }).collect();
+
+    let holiday_list: String = working_days.holidays.iter()
+        .map(|h| format!("{} ({})", h.name, h.date))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    let html_content = format!(
+        r#"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Attendance Report</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 15px; font-size: 11px; }}
+        .header {{ text-align: center; margin-bottom: 20px; border-bottom: 2px solid #dc2626; padding-bottom: 10px; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        th, td {{ border: 1px solid #cbd5e1; padding: 5px; text-align: left; }}
+        th {{ background-color: #f8fafc; font-weight: bold; }}
+        tr:nth-child(even) {{ background-color: #f8fafc; }}
+        @media print {{ .no-print {{ display: none; }} body {{ margin: 0; }} }}
+        @page {{ size: {}; margin: 10mm; }}
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>Employee Management System</h1>
+        <h2>Attendance Report {:02}/{}</h2>
+        <p>Working Days: {} | Weekend Days: {} | Holidays: {} | Generated on {}</p>
+        <p>{}</p>
+    </div>
+
+    <table>
+        <thead>
+            <tr>
+                <th>Employee #</th>
+                <th>Full Name</th>
+                <th>Present</th>
+                <th>Absent</th>
+                <th>Half Days</th>
+                <th>Official Duty</th>
+                <th>Leave</th>
+                <th>No Pay</th>
+                <th>Days Attended</th>
+                <th>Attendance</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <div class="no-print" style="text-align: center; margin-top: 20px;">
+        <button onclick="window.print()" style="background: #dc2626; color: white; padding: 10px 20px; border: none; border-radius: 4px; cursor: pointer;">Print Report</button>
+    </div>
+</body>
+</html>
+        "#,
+        options.orientation,
+        working_days.month,
+        working_days.year,
+        working_days.working_days,
+        working_days.weekend_days,
+        working_days.holidays.len(),
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
+        holiday_list,
+        table_rows
+    );
+
+    fs::write(&file_path, html_content)
+        .map_err(|e| format!("Failed to write HTML file: {}", e))?;
+
     Ok(PrintResponse {
-        success: false,
-        file_path: None,
-        message: None,
-        error: Some("Attendance HTML report not yet implemented".to_string()),
+        success: true,
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        message: Some("Attendance HTML report generated successfully".to_string()),
+        error: None,
     })
 }
 
@@ -529,4 +649,205 @@ async fn generate_pdf_attendance_report(
         message: None,
         error: Some("PDF generation not yet implemented".to_string()),
     })
-}
\ No newline at end of file
+}
+
+pub(crate) async fn generate_html_rollover_report(
+    report: &LeaveRolloverReport,
+    options: PrintOptions,
+) -> Result<PrintResponse, String> {
+    let downloads_dir = tauri::api::path::download_dir()
+        .ok_or_else(|| "Failed to get downloads directory".to_string())?;
+
+    let file_path = downloads_dir.join(&options.filename);
+
+    let table_rows: String = report.entries.iter().map(|entry| {
+        format!(
+            r#"
+            <tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>
+            "#,
+            entry.employee_number,
+            entry.full_name,
+            entry.unused,
+            entry.carried_forward,
+            entry.lapsed
+        )
+    }).collect();
+
+    let html_content = format!(
+        r#"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Leave Rollover Report</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 15px; font-size: 12px; }}
+        .header {{ text-align: center; margin-bottom: 20px; border-bottom: 2px solid #dc2626; padding-bottom: 10px; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        th, td {{ border: 1px solid #cbd5e1; padding: 6px; text-align: left; }}
+        th {{ background-color: #f8fafc; font-weight: bold; }}
+        tr:nth-child(even) {{ background-color: #f8fafc; }}
+        @media print {{ .no-print {{ display: none; }} body {{ margin: 0; }} }}
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>Employee Management System</h1>
+        <h2>Vacation Leave Rollover {} to {}</h2>
+        <p>Carry-forward cap: {} days | Total carried: {} | Total lapsed: {} | Generated on {}</p>
+    </div>
+
+    <table>
+        <thead>
+            <tr>
+                <th>Employee #</th>
+                <th>Full Name</th>
+                <th>Unused Days</th>
+                <th>Carried Forward</th>
+                <th>Lapsed</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <div class="no-print" style="text-align: center; margin-top: 20px;">
+        <button onclick="window.print()" style="background: #dc2626; color: white; padding: 10px 20px; border: none; border-radius: 4px; cursor: pointer;">Print Report</button>
+    </div>
+</body>
+</html>
+        "#,
+        report.from_year,
+        report.to_year,
+        report.carry_forward_cap,
+        report.total_carried_forward,
+        report.total_lapsed,
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
+        table_rows
+    );
+
+    fs::write(&file_path, html_content)
+        .map_err(|e| format!("Failed to write HTML file: {}", e))?;
+
+    Ok(PrintResponse {
+        success: true,
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        message: Some("Rollover report generated successfully".to_string()),
+        error: None,
+    })
+}
+
+pub(crate) async fn generate_html_anomaly_report(
+    report: &AnomalyReport,
+    options: PrintOptions,
+) -> Result<PrintResponse, String> {
+    let downloads_dir = tauri::api::path::download_dir()
+        .ok_or_else(|| "Failed to get downloads directory".to_string())?;
+
+    let file_path = downloads_dir.join(&options.filename);
+
+    let table_rows: String = report.anomalies.iter().map(|anomaly| {
+        let anomaly_type = match anomaly.anomaly_type {
+            AnomalyType::ConsecutiveAbsence => "Consecutive absence",
+            AnomalyType::MissingRecord => "Missing record",
+            AnomalyType::DuplicateDay => "Duplicate day",
+            AnomalyType::InvalidDay => "Invalid day",
+            AnomalyType::AfterRetirement => "After retirement",
+            AnomalyType::AfterTransfer => "After transfer",
+        };
+        format!(
+            r#"
+            <tr>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{:02}/{}</td>
+                <td>{}</td>
+                <td>{}</td>
+                <td>{}</td>
+            </tr>
+            "#,
+            anomaly.employee_number,
+            anomaly.full_name,
+            anomaly.month,
+            anomaly.year,
+            anomaly_type,
+            anomaly.description,
+            anomaly.dates.join(", ")
+        )
+    }).collect();
+
+    let html_content = format!(
+        r#"
+<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="UTF-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1.0">
+    <title>Attendance Anomaly Report</title>
+    <style>
+        body {{ font-family: Arial, sans-serif; margin: 15px; font-size: 12px; }}
+        .header {{ text-align: center; margin-bottom: 20px; border-bottom: 2px solid #dc2626; padding-bottom: 10px; }}
+        table {{ width: 100%; border-collapse: collapse; }}
+        th, td {{ border: 1px solid #cbd5e1; padding: 6px; text-align: left; }}
+        th {{ background-color: #f8fafc; font-weight: bold; }}
+        tr:nth-child(even) {{ background-color: #f8fafc; }}
+        @media print {{ .no-print {{ display: none; }} body {{ margin: 0; }} }}
+    </style>
+</head>
+<body>
+    <div class="header">
+        <h1>Employee Management System</h1>
+        <h2>Attendance Anomalies {:02}/{} to {:02}/{}</h2>
+        <p>Consecutive absence limit: {} days | Anomalies found: {} | Generated on {}</p>
+    </div>
+
+    <table>
+        <thead>
+            <tr>
+                <th>Employee #</th>
+                <th>Full Name</th>
+                <th>Month</th>
+                <th>Anomaly</th>
+                <th>Details</th>
+                <th>Dates</th>
+            </tr>
+        </thead>
+        <tbody>
+            {}
+        </tbody>
+    </table>
+
+    <div class="no-print" style="text-align: center; margin-top: 20px;">
+        <button onclick="window.print()" style="background: #dc2626; color: white; padding: 10px 20px; border: none; border-radius: 4px; cursor: pointer;">Print Report</button>
+    </div>
+</body>
+</html>
+        "#,
+        report.from_month,
+        report.from_year,
+        report.to_month,
+        report.to_year,
+        report.max_consecutive_absences,
+        report.anomalies.len(),
+        chrono::Utc::now().format("%Y-%m-%d %H:%M:%S"),
+        table_rows
+    );
+
+    fs::write(&file_path, html_content)
+        .map_err(|e| format!("Failed to write HTML file: {}", e))?;
+
+    Ok(PrintResponse {
+        success: true,
+        file_path: Some(file_path.to_string_lossy().to_string()),
+        message: Some("Anomaly report generated successfully".to_string()),
+        error: None,
+    })
+}
@@ -0,0 +1,165 @@
+use crate::models::{settings::*, employee::Employee, dates::to_bson_date};
+use crate::database::{Collections, helpers::next_readable};
+use crate::AppState;
+use mongodb::bson::{doc, DateTime, RawDocumentBuf};
+use mongodb::options::ReplaceOptions;
+use mongodb::Database;
+use tauri::State;
+
+/// Key of the attendance settings document in the settings collection
+const ATTENDANCE_SETTINGS_ID: &str = "attendance";
+
+/// Key of the retirement settings document in the settings collection
+const RETIREMENT_SETTINGS_ID: &str = "retirement";
+
+/// Get the office hours and short leave rules
+#[tauri::command]
+pub async fn get_attendance_settings(
+    state: State<'_, AppState>,
+) -> Result<AttendanceSettings, String> {
+    load_attendance_settings(&state.db).await
+}
+
+/// Update the office hours and short leave rules
+#[tauri::command]
+pub async fn update_attendance_settings(
+    state: State<'_, AppState>,
+    settings: AttendanceSettings,
+) -> Result<AttendanceSettingsResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<AttendanceSettings>(Collections::SETTINGS);
+
+    if let Err(e) = settings.validate() {
+        return Ok(AttendanceSettingsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(e),
+        });
+    }
+
+    let mut settings = settings;
+    settings.updated_at = Some(DateTime::now());
+
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    match collection.replace_one(doc! { "_id": ATTENDANCE_SETTINGS_ID }, &settings, options).await {
+        Ok(_) => Ok(AttendanceSettingsResponse {
+            success: true,
+            data: Some(settings),
+            message: Some("Attendance settings updated successfully".to_string()),
+            error: None,
+        }),
+        Err(e) => Ok(AttendanceSettingsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Failed to update attendance settings: {}", e)),
+        }),
+    }
+}
+
+/// Get the retirement age of each service category
+#[tauri::command]
+pub async fn get_retirement_settings(
+    state: State<'_, AppState>,
+) -> Result<RetirementSettings, String> {
+    load_retirement_settings(&state.db).await
+}
+
+/// Update the retirement ages and recalculate the stored retirement dates
+#[tauri::command]
+pub async fn update_retirement_settings(
+    state: State<'_, AppState>,
+    settings: RetirementSettings,
+) -> Result<RetirementSettingsResponse, String> {
+    let db = &state.db;
+    let collection = db.collection::<RetirementSettings>(Collections::SETTINGS);
+
+    if let Err(e) = settings.validate() {
+        return Ok(RetirementSettingsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(e),
+        });
+    }
+
+    let mut settings = settings;
+    settings.updated_at = Some(DateTime::now());
+
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    if let Err(e) = collection.replace_one(doc! { "_id": RETIREMENT_SETTINGS_ID }, &settings, options).await {
+        return Ok(RetirementSettingsResponse {
+            success: false,
+            data: None,
+            message: None,
+            error: Some(format!("Failed to update retirement settings: {}", e)),
+        });
+    }
+
+    let updated = refresh_retirement_dates(db, &settings).await?;
+
+    Ok(RetirementSettingsResponse {
+        success: true,
+        data: Some(settings),
+        message: Some(format!("Retirement settings updated, {} retirement dates recalculated", updated)),
+        error: None,
+    })
+}
+
+/// Load the retirement settings, falling back to the defaults
+pub(crate) async fn load_retirement_settings(db: &Database) -> Result<RetirementSettings, String> {
+    let collection = db.collection::<RetirementSettings>(Collections::SETTINGS);
+
+    let settings = collection.find_one(doc! { "_id": RETIREMENT_SETTINGS_ID }, None)
+        .await
+        .map_err(|e| format!("Failed to load retirement settings: {}", e))?;
+
+    Ok(settings.unwrap_or_default())
+}
+
+/// Store the retirement date derived from the settings on every employee whose date differs
+async fn refresh_retirement_dates(db: &Database, settings: &RetirementSettings) -> Result<u64, String> {
+    let collection = db.collection::<Employee>(Collections::EMPLOYEES);
+    let today = chrono::Local::now().date_naive();
+
+    let mut cursor = collection.clone_with_type::<RawDocumentBuf>().find(doc! {}, None)
+        .await
+        .map_err(|e| format!("Failed to find employees: {}", e))?;
+
+    let mut updated = 0;
+    while let Some(mut employee) = next_readable::<Employee>(&mut cursor)
+        .await
+        .map_err(|e| format!("Failed to iterate employees: {}", e))? {
+        let stored = employee.retired_date;
+        employee.derive_dates(settings, today);
+
+        let (id, retired_date) = match (employee.id, employee.retired_date) {
+            (Some(id), Some(retired_date)) if stored != Some(retired_date) => (id, retired_date),
+            _ => continue,
+        };
+
+        collection.update_one(
+            doc! { "_id": id },
+            doc! { "$set": { "retiredDate": to_bson_date(&retired_date), "age": employee.age } },
+            None
+        ).await
+            .map_err(|e| format!("Failed to update retirement date: {}", e))?;
+        updated += 1;
+    }
+
+    Ok(updated)
+}
+
+/// Load the attendance settings, falling back to the defaults
+pub(crate) async fn load_attendance_settings(db: &Database) -> Result<AttendanceSettings, String> {
+    let collection = db.collection::<AttendanceSettings>(Collections::SETTINGS);
+
+    let settings = collection.find_one(doc! { "_id": ATTENDANCE_SETTINGS_ID }, None)
+        .await
+        .map_err(|e| format!("Failed to load attendance settings: {}", e))?;
+
+    Ok(settings.unwrap_or_default())
+}
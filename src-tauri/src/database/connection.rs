@@ -1,4 +1,7 @@
-use mongodb::{Client, Database, options::ClientOptions};
+use mongodb::{Client, Database, IndexModel};
+use mongodb::bson::{doc, Document};
+use mongodb::options::{ClientOptions, Collation, CollationStrength, FindOptions, IndexOptions};
+use crate::models::nic::Nic;
 use std::env;
 use anyhow::Result;
 
@@ -29,8 +32,126 @@ pub async fn init_database() -> Result<Database> {
 
     println!("Connected to MongoDB successfully!");
 
+    let db = client.database(&db_name);
+    for error in ensure_indexes(&db).await {
+        eprintln!("{}", error);
+    }
+
     // Return database instance
-    Ok(client.database(&db_name))
+    Ok(db)
+}
+
+/// Create the unique indexes the application relies on, returning an error for each index
+/// that cannot be built, for example because duplicates already exist. Startup carries on
+/// without them; get_duplicate_employees lists the conflicting records and builds them again.
+pub async fn ensure_indexes(db: &Database) -> Vec<String> {
+    let employees = db.collection::<Document>(Collections::EMPLOYEES);
+
+    // NIC numbers are compared in the 12 digit format, so old and new forms of one number clash
+    let mut errors = normalize_nic_numbers(db).await;
+
+    // Blank NIC numbers and emails of older records are left out of the indexes
+    let indexes = vec![
+        IndexModel::builder()
+            .keys(doc! { "employeeNumber": 1 })
+            .options(IndexOptions::builder()
+                .name(Indexes::EMPLOYEE_NUMBER.to_string())
+                .unique(true)
+                .build())
+            .build(),
+        IndexModel::builder()
+            .keys(doc! { "nicNumber": 1 })
+            .options(IndexOptions::builder()
+                .name(Indexes::NIC_NUMBER.to_string())
+                .unique(true)
+                .partial_filter_expression(doc! { "nicNumber": { "$gt": "" } })
+                .build())
+            .build(),
+        IndexModel::builder()
+            .keys(doc! { "emailAddress": 1 })
+            .options(IndexOptions::builder()
+                .name(Indexes::EMAIL_ADDRESS.to_string())
+                .unique(true)
+                .partial_filter_expression(doc! { "emailAddress": { "$gt": "" } })
+                // Emails differing only in case are the same address
+                .collation(Collation::builder()
+                    .locale("en")
+                    .strength(CollationStrength::Secondary)
+                    .build())
+                .build())
+            .build(),
+    ];
+
+    for index in indexes {
+        let name = index.options.as_ref()
+            .and_then(|o| o.name.clone())
+            .unwrap_or_default();
+        if let Err(e) = employees.create_index(index, None).await {
+            errors.push(format!("Failed to create index {}: {}", name, e));
+        }
+    }
+
+    errors
+}
+
+/// Store NIC numbers entered before they were validated in the 12 digit format.
+/// Numbers that cannot be read are left as they are and reported.
+async fn normalize_nic_numbers(db: &Database) -> Vec<String> {
+    use futures::stream::TryStreamExt;
+
+    let employees = db.collection::<Document>(Collections::EMPLOYEES);
+    let options = FindOptions::builder()
+        .projection(doc! { "nicNumber": 1, "employeeNumber": 1 })
+        .build();
+
+    // Only the old 9 digit numbers need converting
+    let mut cursor = match employees.find(doc! { "nicNumber": { "$regex": "^[0-9]{9}[VvXx]$" } }, options).await {
+        Ok(cursor) => cursor,
+        Err(e) => return vec![format!("Failed to find NIC numbers: {}", e)],
+    };
+
+    let mut errors = Vec::new();
+    loop {
+        let employee = match cursor.try_next().await {
+            Ok(Some(employee)) => employee,
+            Ok(None) => break,
+            Err(e) => {
+                errors.push(format!("Failed to iterate NIC numbers: {}", e));
+                break;
+            }
+        };
+        let (id, stored) = match (employee.get_object_id("_id"), employee.get_str("nicNumber")) {
+            (Ok(id), Ok(stored)) => (id, stored),
+            _ => continue,
+        };
+        let normalized = match Nic::parse(stored) {
+            Ok(nic) => nic.to_new_format(),
+            Err(e) => {
+                errors.push(format!(
+                    "Invalid NIC number {} of employee {}: {}",
+                    stored,
+                    employee.get_str("employeeNumber").unwrap_or_default(),
+                    e
+                ));
+                continue;
+            }
+        };
+        if normalized == stored {
+            continue;
+        }
+
+        if let Err(e) = employees.update_one(doc! { "_id": id }, doc! { "$set": { "nicNumber": &normalized } }, None).await {
+            errors.push(format!(
+                "Failed to store NIC number {} of employee {} as {}: {}",
+                stored,
+                employee.get_str("employeeNumber").unwrap_or_default(),
+                normalized,
+                e
+            ));
+        }
+    }
+
+    errors
 }
 
 /// Get collection names used in the application
@@ -40,6 +161,26 @@ impl Collections {
     pub const EMPLOYEES: &'static str = "employees";
     pub const ATTENDANCE: &'static str = "attendance";
     pub const LEAVES: &'static str = "leaves";
+    pub const LEAVE_ENTITLEMENTS: &'static str = "leave_entitlements";
+    pub const LEAVE_LEDGER: &'static str = "leave_ledger";
+    pub const HOLIDAYS: &'static str = "holidays";
+    pub const SETTINGS: &'static str = "settings";
+    pub const ATTENDANCE_LOCKS: &'static str = "attendance_locks";
+    pub const ATTENDANCE_ARCHIVE: &'static str = "attendance_archive";
+    pub const LEAVES_ARCHIVE: &'static str = "leaves_archive";
+    pub const LEAVE_LEDGER_ARCHIVE: &'static str = "leave_ledger_archive";
+    pub const EMPLOYMENT_STATUS_HISTORY: &'static str = "employment_status_history";
+    pub const EMPLOYMENT_STATUS_HISTORY_ARCHIVE: &'static str = "employment_status_history_archive";
+    pub const NOTIFICATION_STATE: &'static str = "notification_state";
+}
+
+/// Names of the unique indexes, used to tell which field a duplicate-key error is about
+pub struct Indexes;
+
+impl Indexes {
+    pub const EMPLOYEE_NUMBER: &'static str = "employeeNumber_unique";
+    pub const NIC_NUMBER: &'static str = "nicNumber_unique";
+    pub const EMAIL_ADDRESS: &'static str = "emailAddress_unique";
 }
 
 /// Database helper functions
@@ -47,6 +188,56 @@ pub mod helpers {
     use mongodb::bson::{doc, oid::ObjectId};
     use serde::{Deserialize, Serialize};
 
+    /// Serialize a model the way the driver stores it, so typed dates become BSON dates
+    pub fn to_stored_document<T: Serialize>(value: &T) -> Result<mongodb::bson::Document, String> {
+        mongodb::bson::to_raw_document_buf(value)
+            .map_err(|e| e.to_string())?
+            .to_document()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Next document of the cursor that can be read as `T`. Documents that cannot be read,
+    /// such as an employee whose date of birth is not a date, are reported and skipped
+    /// so one bad record does not fail a whole list.
+    pub async fn next_readable<T: serde::de::DeserializeOwned>(
+        cursor: &mut mongodb::Cursor<mongodb::bson::RawDocumentBuf>,
+    ) -> mongodb::error::Result<Option<T>> {
+        use futures::stream::TryStreamExt;
+
+        while let Some(document) = cursor.try_next().await? {
+            match mongodb::bson::from_slice(document.as_bytes()) {
+                Ok(value) => return Ok(Some(value)),
+                Err(e) => eprintln!(
+                    "Skipping unreadable record {}: {}",
+                    document.get_object_id("_id").map(|id| id.to_hex()).unwrap_or_default(),
+                    e
+                ),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// The name of the unique index a write was rejected by, if it failed on a duplicate key
+    pub fn duplicate_key_index(error: &mongodb::error::Error) -> Option<String> {
+        use mongodb::error::{ErrorKind, WriteFailure};
+
+        let (code, message) = match error.kind.as_ref() {
+            ErrorKind::Write(WriteFailure::WriteError(e)) => (e.code, &e.message),
+            ErrorKind::Command(e) => (e.code, &e.message),
+            _ => return None,
+        };
+        if code != 11000 {
+            return None;
+        }
+
+        // E11000 duplicate key error collection: db.employees index: <name> dup key: { ... }
+        message.split("index: ")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .map(|name| name.to_string())
+    }
+
     /// Convert string ID to ObjectId
     pub fn string_to_object_id(id: &str) -> Result<ObjectId, mongodb::bson::oid::Error> {
         ObjectId::parse_str(id)
@@ -63,6 +254,18 @@ pub mod helpers {
         Ok(doc! { "_id": object_id })
     }
 
+    /// Escape regex metacharacters so user input matches literally in a $regex filter
+    pub fn escape_regex(value: &str) -> String {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            if "\\.+*?()|[]{}^$#&-~".contains(c) {
+                escaped.push('\\');
+            }
+            escaped.push(c);
+        }
+        escaped
+    }
+
     /// Pagination helper
     #[derive(Debug, Serialize, Deserialize)]
     pub struct PaginationOptions {
@@ -3,7 +3,7 @@
 
 use employee_management_system::{
     AppState,
-    commands::{employee::*, attendance::*, print::*},
+    commands::{employee::*, attendance::*, leave::*, holiday::*, settings::*, biometric::*, anomaly::*, dashboard::*, notification::*, print::*},
     database::connection::init_database,
 };
 use tauri::{Manager};
@@ -21,8 +21,42 @@ async fn main() {
             tauri::async_runtime::spawn(async move {
                 match init_database().await {
                     Ok(db) => {
-                        handle.manage(AppState { db });
+                        // Convert legacy string dates and statuses before anything reads the employees
+                        match run_employee_date_migration(&db).await {
+                            Ok(migration) => {
+                                for invalid in &migration.invalid_dates {
+                                    eprintln!(
+                                        "Unreadable {} '{}' on employee {}",
+                                        invalid.field, invalid.value, invalid.employee_number
+                                    );
+                                }
+                                if let Some(message) = migration.message {
+                                    println!("{}", message);
+                                }
+                            }
+                            Err(e) => eprintln!("Failed to migrate employee dates: {}", e),
+                        }
+
+                        match run_employee_status_migration(&db).await {
+                            Ok(migration) => {
+                                for invalid in &migration.invalid_statuses {
+                                    eprintln!(
+                                        "Unknown status '{}' on employee {}",
+                                        invalid.value, invalid.employee_number
+                                    );
+                                }
+                                if let Some(message) = migration.message {
+                                    println!("{}", message);
+                                }
+                            }
+                            Err(e) => eprintln!("Failed to migrate employment statuses: {}", e),
+                        }
+
+                        handle.manage(AppState { db: db.clone() });
                         println!("Database initialized successfully");
+
+                        // Check HR deadlines in the background for as long as the app runs
+                        tauri::async_runtime::spawn(run_notification_scheduler(handle.clone(), db));
                     }
                     Err(e) => {
                         eprintln!("Failed to initialize database: {}", e);
@@ -41,12 +75,62 @@ async fn main() {
             update_employee,
             delete_employee,
             search_employees,
+            archive_employee,
+            restore_employee,
+            change_employment_status,
+            get_employment_status_history,
+            get_orphaned_records,
+            cleanup_orphaned_records,
+            migrate_employee_dates,
+            migrate_employee_statuses,
+            get_duplicate_employees,
             // Attendance commands
             get_attendance_records,
             create_attendance_record,
             update_attendance_record,
             delete_attendance_record,
             get_monthly_summary,
+            get_yearly_summary,
+            get_employee_attendance_history,
+            mark_attendance_bulk,
+            lock_month,
+            unlock_month,
+            get_attendance_locks,
+            resync_employee_numbers,
+            // Leave commands
+            get_leave_applications,
+            create_leave_application,
+            update_leave_application,
+            approve_leave_application,
+            reject_leave_application,
+            cancel_leave_application,
+            get_leave_balance,
+            get_leave_entitlements,
+            set_leave_entitlement,
+            delete_leave_entitlement,
+            run_leave_rollover,
+            // Holiday commands
+            get_holidays,
+            create_holiday,
+            update_holiday,
+            delete_holiday,
+            import_holidays_ics,
+            get_working_days,
+            // Settings commands
+            get_attendance_settings,
+            update_attendance_settings,
+            get_retirement_settings,
+            update_retirement_settings,
+            // Biometric commands
+            import_biometric_log,
+            // Anomaly commands
+            detect_attendance_anomalies,
+            // Dashboard commands
+            get_upcoming_events,
+            // Notification commands
+            get_deadline_alerts,
+            dismiss_deadline_alert,
+            snooze_deadline_alert,
             // Print commands
             generate_employee_report,
             generate_bulk_report,
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AnomalyType {
+    #[serde(rename = "consecutive-absence")]
+    ConsecutiveAbsence, // Absent for more working days in a row than allowed, without leave
+    #[serde(rename = "missing-record")]
+    MissingRecord, // No attendance record for a month the employee was in service
+    #[serde(rename = "duplicate-day")]
+    DuplicateDay, // The same day entered more than once in a record
+    #[serde(rename = "invalid-day")]
+    InvalidDay, // A day outside the length of the month
+    #[serde(rename = "after-retirement")]
+    AfterRetirement,
+    #[serde(rename = "after-transfer")]
+    AfterTransfer,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttendanceAnomaly {
+    #[serde(rename = "anomalyType")]
+    pub anomaly_type: AnomalyType,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "fullName")]
+    pub full_name: String,
+    pub month: i32, // Month the anomaly starts in
+    pub year: i32,
+    pub dates: Vec<String>, // dd-MM-yyyy, or the raw day numbers for invalid days
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AnomalyReport {
+    #[serde(rename = "fromMonth")]
+    pub from_month: i32,
+    #[serde(rename = "fromYear")]
+    pub from_year: i32,
+    #[serde(rename = "toMonth")]
+    pub to_month: i32,
+    #[serde(rename = "toYear")]
+    pub to_year: i32,
+    #[serde(rename = "maxConsecutiveAbsences")]
+    pub max_consecutive_absences: i32,
+    pub anomalies: Vec<AttendanceAnomaly>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AnomalyReportResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<AnomalyReport>,
+    #[serde(rename = "filePath", skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
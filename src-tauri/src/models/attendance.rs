@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use mongodb::bson::{oid::ObjectId, DateTime};
+use std::collections::BTreeMap;
+use super::leave::LeaveType;
+use super::settings::{parse_time, AttendanceSettings};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AttendanceRecord {
@@ -18,15 +21,68 @@ pub struct AttendanceRecord {
     pub updated_at: Option<DateTime>,
 }
 
+impl AttendanceRecord {
+    /// Totals of the month, as in the monthly summary
+    pub fn totals(&self, working_days: i32, short_leaves_per_month: i32) -> AttendanceTotals {
+        AttendanceTotals::from_days(&self.records, working_days, short_leaves_per_month)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DailyAttendance {
     pub date: i32, // Day of month (1-31)
     pub status: AttendanceStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    #[serde(rename = "leaveApplicationId", skip_serializing_if = "Option::is_none")]
+    pub leave_application_id: Option<String>, // Set when the day was marked by approved leave
+    #[serde(rename = "previousStatus", skip_serializing_if = "Option::is_none")]
+    pub previous_status: Option<AttendanceStatus>, // Restored when that leave is revoked
+    #[serde(rename = "checkIn", skip_serializing_if = "Option::is_none")]
+    pub check_in: Option<String>, // HH:mm
+    #[serde(rename = "checkOut", skip_serializing_if = "Option::is_none")]
+    pub check_out: Option<String>, // HH:mm
+    #[serde(rename = "isLate", default)]
+    pub is_late: bool, // Calculated from the office hours
+    #[serde(rename = "isEarlyDeparture", default)]
+    pub is_early_departure: bool, // Calculated from the office hours
+    #[serde(rename = "shortLeave", default)]
+    pub short_leave: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+impl DailyAttendance {
+    /// Check the arrival and departure times and flag late arrivals and early departures
+    pub fn apply_office_hours(&mut self, settings: &AttendanceSettings) -> Result<(), String> {
+        let check_in = match self.check_in.as_deref().filter(|t| !t.trim().is_empty()) {
+            Some(time) => Some(parse_time(time)
+                .ok_or_else(|| format!("Invalid arrival time '{}'. Use HH:mm", time))?),
+            None => None,
+        };
+        let check_out = match self.check_out.as_deref().filter(|t| !t.trim().is_empty()) {
+            Some(time) => Some(parse_time(time)
+                .ok_or_else(|| format!("Invalid departure time '{}'. Use HH:mm", time))?),
+            None => None,
+        };
+
+        if let (Some(arrival), Some(departure)) = (check_in, check_out) {
+            if departure < arrival {
+                return Err("Departure time cannot be before the arrival time".to_string());
+            }
+        }
+
+        let office_start = parse_time(&settings.office_start)
+            .ok_or_else(|| "Invalid office start time in settings".to_string())?;
+        let office_end = parse_time(&settings.office_end)
+            .ok_or_else(|| "Invalid office end time in settings".to_string())?;
+        let late_after = office_start + chrono::Duration::minutes(settings.grace_minutes);
+
+        self.is_late = check_in.map(|t| t > late_after).unwrap_or(false);
+        self.is_early_departure = check_out.map(|t| t < office_end).unwrap_or(false);
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum AttendanceStatus {
     #[serde(rename = "present")]
     Present,
@@ -34,6 +90,81 @@ pub enum AttendanceStatus {
     Absent,
     #[serde(rename = "half-day")]
     HalfDay,
+    #[serde(rename = "official-duty")]
+    OfficialDuty,
+    #[serde(rename = "leave")]
+    Leave, // Vacation, maternity and other leave without a dedicated status
+    #[serde(rename = "half-day-leave")]
+    HalfDayLeave,
+    #[serde(rename = "casual-leave")]
+    CasualLeave,
+    #[serde(rename = "sick-leave")]
+    SickLeave,
+    #[serde(rename = "no-pay")]
+    NoPay,
+    #[serde(rename = "holiday")]
+    PublicHoliday,
+    #[serde(rename = "weekend")]
+    Weekend,
+}
+
+impl AttendanceStatus {
+    pub const ALL: [AttendanceStatus; 11] = [
+        AttendanceStatus::Present,
+        AttendanceStatus::Absent,
+        AttendanceStatus::HalfDay,
+        AttendanceStatus::OfficialDuty,
+        AttendanceStatus::Leave,
+        AttendanceStatus::HalfDayLeave,
+        AttendanceStatus::CasualLeave,
+        AttendanceStatus::SickLeave,
+        AttendanceStatus::NoPay,
+        AttendanceStatus::PublicHoliday,
+        AttendanceStatus::Weekend,
+    ];
+
+    /// Number of days with each status (every status is present, zero if unused)
+    pub fn count<'a, I>(days: I) -> BTreeMap<AttendanceStatus, i32>
+    where
+        I: IntoIterator<Item = &'a DailyAttendance>,
+    {
+        let mut counts: BTreeMap<AttendanceStatus, i32> = AttendanceStatus::ALL.iter()
+            .map(|status| (*status, 0))
+            .collect();
+        for daily in days {
+            *counts.entry(daily.status).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Status recorded for a full day of the given leave type
+    pub fn from_leave_type(leave_type: LeaveType) -> Self {
+        match leave_type {
+            LeaveType::CasualLeave => AttendanceStatus::CasualLeave,
+            LeaveType::SickLeave => AttendanceStatus::SickLeave,
+            LeaveType::DutyLeave => AttendanceStatus::OfficialDuty,
+            LeaveType::NoPayLeave => AttendanceStatus::NoPay,
+            LeaveType::VacationLeave | LeaveType::MaternityLeave => AttendanceStatus::Leave,
+        }
+    }
+
+    /// Portion of the day the employee was at work or on official duty
+    pub fn attendance_weight(&self) -> f64 {
+        match self {
+            AttendanceStatus::Present | AttendanceStatus::OfficialDuty => 1.0,
+            AttendanceStatus::HalfDay | AttendanceStatus::HalfDayLeave => 0.5,
+            _ => 0.0,
+        }
+    }
+
+    /// Portion of the day taken as paid leave
+    pub fn leave_weight(&self) -> f64 {
+        match self {
+            AttendanceStatus::Leave | AttendanceStatus::CasualLeave | AttendanceStatus::SickLeave => 1.0,
+            AttendanceStatus::HalfDayLeave => 0.5,
+            _ => 0.0,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -48,16 +179,125 @@ pub struct MonthlyAttendanceSummary {
     pub year: i32,
     #[serde(rename = "totalWorkingDays")]
     pub total_working_days: i32,
-    #[serde(rename = "totalPresent")]
-    pub total_present: i32,
-    #[serde(rename = "totalAbsent")]
-    pub total_absent: i32,
-    #[serde(rename = "totalHalfDays")]
-    pub total_half_days: i32,
+    #[serde(rename = "statusCounts")]
+    pub status_counts: BTreeMap<AttendanceStatus, i32>,
+    #[serde(rename = "totalAttendedDays")]
+    pub total_attended_days: f64,
+    #[serde(rename = "totalLeaveDays")]
+    pub total_leave_days: f64,
+    #[serde(rename = "lateArrivals")]
+    pub late_arrivals: i32,
+    #[serde(rename = "earlyDepartures")]
+    pub early_departures: i32,
+    #[serde(rename = "shortLeavesTaken")]
+    pub short_leaves_taken: i32,
+    #[serde(rename = "shortLeaveExcess")]
+    pub short_leave_excess: i32, // Short leaves beyond the monthly allowance, deducted as half days
+    #[serde(rename = "attendancePercentage")]
+    pub attendance_percentage: f64,
+}
+
+/// Attendance counts over a period, used by the yearly summary and attendance history
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AttendanceTotals {
+    #[serde(rename = "workingDays")]
+    pub working_days: i32,
+    pub present: i32,
+    pub absent: i32,
+    #[serde(rename = "halfDay")]
+    pub half_day: i32,
+    #[serde(rename = "leaveDays")]
+    pub leave_days: f64,
+    #[serde(rename = "attendedDays")]
+    pub attended_days: f64, // After deducting short leaves beyond the monthly allowance
     #[serde(rename = "attendancePercentage")]
     pub attendance_percentage: f64,
 }
 
+impl AttendanceTotals {
+    /// Totals of the given days of a month
+    pub fn from_days<'a, I>(days: I, working_days: i32, short_leaves_per_month: i32) -> Self
+    where
+        I: IntoIterator<Item = &'a DailyAttendance> + Clone,
+    {
+        let short_leaves = days.clone().into_iter().filter(|d| d.short_leave).count() as i32;
+        Self::from_counts(&AttendanceStatus::count(days), short_leaves, working_days, short_leaves_per_month)
+    }
+
+    /// Totals of a month from the days counted per status and the short leaves taken.
+    /// Short leaves beyond the allowance are deducted as half days.
+    pub fn from_counts(
+        status_counts: &BTreeMap<AttendanceStatus, i32>,
+        short_leaves: i32,
+        working_days: i32,
+        short_leaves_per_month: i32,
+    ) -> Self {
+        let count = |status: AttendanceStatus| status_counts.get(&status).copied().unwrap_or(0);
+        let weighted = |weight: fn(&AttendanceStatus) -> f64| -> f64 {
+            status_counts.iter().map(|(status, count)| weight(status) * *count as f64).sum()
+        };
+
+        let short_leave_excess = Self::short_leave_excess(short_leaves, short_leaves_per_month);
+        let mut totals = AttendanceTotals {
+            working_days,
+            present: count(AttendanceStatus::Present),
+            absent: count(AttendanceStatus::Absent),
+            half_day: count(AttendanceStatus::HalfDay),
+            leave_days: weighted(AttendanceStatus::leave_weight),
+            attended_days: (weighted(AttendanceStatus::attendance_weight)
+                - short_leave_excess as f64 * 0.5).max(0.0),
+            attendance_percentage: 0.0,
+        };
+        totals.update_percentage();
+        totals
+    }
+
+    /// Short leaves beyond the monthly allowance
+    pub fn short_leave_excess(short_leaves: i32, short_leaves_per_month: i32) -> i32 {
+        (short_leaves - short_leaves_per_month).max(0)
+    }
+
+    pub fn add(&mut self, other: &AttendanceTotals) {
+        self.working_days += other.working_days;
+        self.present += other.present;
+        self.absent += other.absent;
+        self.half_day += other.half_day;
+        self.leave_days += other.leave_days;
+        self.attended_days += other.attended_days;
+        self.update_percentage();
+    }
+
+    fn update_percentage(&mut self) {
+        self.attendance_percentage = if self.working_days > 0 {
+            (self.attended_days / self.working_days as f64) * 100.0
+        } else {
+            0.0
+        };
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MonthAttendanceTotals {
+    pub month: i32,
+    pub year: i32,
+    pub totals: AttendanceTotals,
+}
+
+/// An employee's attendance rolled up across months
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmployeeAttendanceHistory {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "fullName")]
+    pub full_name: String,
+    pub from: String, // dd-MM-yyyy
+    pub to: String, // dd-MM-yyyy
+    pub months: Vec<MonthAttendanceTotals>, // Months with an attendance record
+    pub total: AttendanceTotals,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AttendanceFilter {
     #[serde(rename = "employeeNumber", skip_serializing_if = "Option::is_none")]
@@ -71,7 +311,9 @@ pub struct AttendanceFilter {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub designation: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub department: Option<String>,
+    pub department: Option<String>, // Matched against the employee's ministry
+    #[serde(rename = "matchingDaysOnly", default)]
+    pub matching_days_only: bool, // Only return the days matching `status`
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -87,10 +329,67 @@ pub struct CreateAttendanceRequest {
 pub struct UpdateAttendanceRequest {
     #[serde(rename = "employeeId")]
     pub employee_id: String,
-    pub date: i32,
+    pub month: i32, // 1-12
+    pub year: i32,
+    pub date: i32, // Day of month (1-31)
     pub status: AttendanceStatus,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
+    #[serde(rename = "checkIn", skip_serializing_if = "Option::is_none")]
+    pub check_in: Option<String>,
+    #[serde(rename = "checkOut", skip_serializing_if = "Option::is_none")]
+    pub check_out: Option<String>,
+    #[serde(rename = "shortLeave", default)]
+    pub short_leave: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkAttendanceEntry {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    pub status: AttendanceStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(rename = "checkIn", skip_serializing_if = "Option::is_none")]
+    pub check_in: Option<String>,
+    #[serde(rename = "checkOut", skip_serializing_if = "Option::is_none")]
+    pub check_out: Option<String>,
+    #[serde(rename = "shortLeave", default)]
+    pub short_leave: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkAttendanceResult {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    pub status: AttendanceStatus,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Lock on a month's attendance once it has been submitted to the divisional office
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttendanceLock {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub month: i32,
+    pub year: i32,
+    pub locked: bool,
+    #[serde(default)]
+    pub history: Vec<AttendanceLockEvent>, // Every lock and unlock, oldest first
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttendanceLockEvent {
+    pub locked: bool, // true for a lock, false for an unlock
+    pub user: String,
+    pub reason: String,
+    pub at: DateTime,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -113,4 +412,60 @@ pub struct MonthlySummaryResponse {
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
-}
\ No newline at end of file
+}
+#[derive(Debug, Serialize, Deserialize)]
+pub struct YearlySummaryResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<EmployeeAttendanceHistory>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttendanceHistoryResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<EmployeeAttendanceHistory>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BulkAttendanceResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<BulkAttendanceResult>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttendanceLockResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<AttendanceLock>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeNumberSyncResponse {
+    pub success: bool,
+    #[serde(rename = "attendanceUpdated")]
+    pub attendance_updated: u64,
+    #[serde(rename = "leaveApplicationsUpdated")]
+    pub leave_applications_updated: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use super::attendance::AttendanceStatus;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PunchLogFormat {
+    #[serde(rename = "zkteco")]
+    ZkTeco, // Tab separated attlog: user id, timestamp, device, state, verify mode, work code
+    #[serde(rename = "csv")]
+    Csv, // Delimited file described by a column mapping
+}
+
+/// Columns of a generic punch log. Each value is a header name when the file
+/// has headers, otherwise a zero-based column index.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PunchLogColumns {
+    #[serde(rename = "userId")]
+    pub user_id: String,
+    pub timestamp: String, // Date and time, or only the date when `time` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub direction: Option<String>, // In/out state of the punch
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PunchLogImportOptions {
+    pub format: PunchLogFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delimiter: Option<String>,
+    #[serde(rename = "hasHeaders", default)]
+    pub has_headers: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub columns: Option<PunchLogColumns>,
+    #[serde(rename = "timestampFormat", skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>, // chrono format; when absent each of the common formats is tried in turn
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum PunchDirection {
+    #[serde(rename = "in")]
+    In,
+    #[serde(rename = "out")]
+    Out,
+}
+
+/// Attendance derived from the punches of one employee on one day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ImportedAttendanceDay {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "deviceUserId")]
+    pub device_user_id: String,
+    pub date: String, // dd-MM-yyyy
+    pub status: AttendanceStatus,
+    #[serde(rename = "checkIn", skip_serializing_if = "Option::is_none")]
+    pub check_in: Option<String>, // HH:mm
+    #[serde(rename = "checkOut", skip_serializing_if = "Option::is_none")]
+    pub check_out: Option<String>, // HH:mm
+    #[serde(rename = "isLate")]
+    pub is_late: bool,
+    #[serde(rename = "isEarlyDeparture")]
+    pub is_early_departure: bool,
+    pub punches: usize,
+    #[serde(rename = "keptExisting")]
+    pub kept_existing: bool, // Left unchanged: leave, a holiday or official duty is recorded, or the month is locked
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PunchLogImportResult {
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    #[serde(rename = "totalLines")]
+    pub total_lines: usize,
+    #[serde(rename = "parsedPunches")]
+    pub parsed_punches: usize,
+    pub days: Vec<ImportedAttendanceDay>,
+    #[serde(rename = "unmatchedUserIds")]
+    pub unmatched_user_ids: Vec<String>,
+    pub errors: Vec<String>, // Lines that could not be parsed
+    #[serde(rename = "recordsUpdated")]
+    pub records_updated: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PunchLogImportResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<PunchLogImportResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+use crate::models::employee::Designation;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UpcomingEventType {
+    #[serde(rename = "retirement")]
+    Retirement,
+    #[serde(rename = "increment")]
+    Increment,
+    #[serde(rename = "service-confirmation")]
+    ServiceConfirmation, // Probation over without the service being confirmed
+    #[serde(rename = "eb-exam")]
+    EbExam, // Efficiency bar exam not passed
+    #[serde(rename = "second-language")]
+    SecondLanguage, // Second language proficiency not passed
+    #[serde(rename = "birthday")]
+    Birthday,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpcomingEvent {
+    #[serde(rename = "eventType")]
+    pub event_type: UpcomingEventType,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "fullName")]
+    pub full_name: String,
+    pub designation: Designation,
+    pub date: String, // dd-MM-yyyy
+    #[serde(rename = "daysUntil")]
+    pub days_until: i64, // Negative when overdue
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpcomingEventCount {
+    #[serde(rename = "eventType")]
+    pub event_type: UpcomingEventType,
+    pub count: usize,
+    pub overdue: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UpcomingEvents {
+    #[serde(rename = "windowDays")]
+    pub window_days: i64,
+    pub from: String, // dd-MM-yyyy
+    pub to: String,
+    pub events: Vec<UpcomingEvent>, // By date, overdue first
+    pub counts: Vec<UpcomingEventCount>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpcomingEventsResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<UpcomingEvents>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
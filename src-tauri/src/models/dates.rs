@@ -0,0 +1,114 @@
+use chrono::{Datelike, NaiveDate, NaiveTime};
+use mongodb::bson::{Bson, DateTime};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Date format used throughout the application (dd-MM-yyyy)
+pub const DATE_FORMAT: &str = "%d-%m-%Y";
+
+/// Parse a dd-MM-yyyy date string
+pub fn parse_date(value: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(value.trim(), DATE_FORMAT).ok()
+}
+
+/// Format a date as dd-MM-yyyy
+pub fn format_date(date: &NaiveDate) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+/// Number of days in the given month, or None for an invalid month
+pub fn days_in_month(year: i32, month: u32) -> Option<u32> {
+    let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+    let next = if month == 12 {
+        NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+    } else {
+        NaiveDate::from_ymd_opt(year, month + 1, 1)?
+    };
+    Some(next.signed_duration_since(first).num_days() as u32)
+}
+
+/// The same day `years` later (or earlier), with 29 February falling back to the 28th
+pub fn add_years(date: &NaiveDate, years: i32) -> NaiveDate {
+    let year = date.year() + years;
+    NaiveDate::from_ymd_opt(year, date.month(), date.day())
+        .or_else(|| NaiveDate::from_ymd_opt(year, date.month(), 28))
+        .unwrap_or(*date)
+}
+
+/// Whole years from `from` to `to`, as for an age
+pub fn years_between(from: &NaiveDate, to: &NaiveDate) -> i32 {
+    let years = to.year() - from.year();
+    if (to.month(), to.day()) < (from.month(), from.day()) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+/// Convert a date to a BSON date at midnight UTC
+pub fn to_bson_date(date: &NaiveDate) -> DateTime {
+    DateTime::from_millis(date.and_time(NaiveTime::MIN).and_utc().timestamp_millis())
+}
+
+/// The calendar date of a BSON date, taken in UTC
+pub fn from_bson_date(value: &DateTime) -> Option<NaiveDate> {
+    chrono::DateTime::from_timestamp_millis(value.timestamp_millis()).map(|d| d.date_naive())
+}
+
+/// Read a date stored either as a BSON date or as a dd-MM-yyyy string.
+/// Blank strings and nulls read as no date.
+fn date_from_bson<E: de::Error>(value: Bson) -> Result<Option<NaiveDate>, E> {
+    match value {
+        Bson::Null => Ok(None),
+        Bson::String(s) if s.trim().is_empty() => Ok(None),
+        Bson::String(s) => parse_date(&s)
+            .map(Some)
+            .ok_or_else(|| E::custom(format!("invalid date '{}', expected dd-MM-yyyy", s))),
+        Bson::DateTime(d) => from_bson_date(&d)
+            .map(Some)
+            .ok_or_else(|| E::custom("date out of range")),
+        other => Err(E::custom(format!("expected a date, found {}", other))),
+    }
+}
+
+/// Serde adapter for a required date. It is a dd-MM-yyyy string at the Tauri boundary
+/// and a BSON date in MongoDB, where dates stored as strings are still read.
+///
+/// Build update documents with `helpers::to_stored_document` rather than `to_bson`,
+/// which presents itself as human readable and would write the string form.
+pub mod date {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &NaiveDate, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&format_date(value))
+        } else {
+            to_bson_date(value).serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveDate, D::Error> {
+        date_from_bson(Bson::deserialize(deserializer)?)?
+            .ok_or_else(|| de::Error::custom("a date is required"))
+    }
+}
+
+/// Serde adapter for an optional date, as `date`. No date is an empty string at the
+/// Tauri boundary and null in MongoDB.
+pub mod optional_date {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(value: &Option<NaiveDate>, serializer: S) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(date) => super::date::serialize(date, serializer),
+            None if serializer.is_human_readable() => serializer.serialize_str(""),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<NaiveDate>, D::Error> {
+        match Option::<Bson>::deserialize(deserializer)? {
+            Some(value) => date_from_bson(value),
+            None => Ok(None),
+        }
+    }
+}
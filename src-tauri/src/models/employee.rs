@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 use mongodb::bson::{oid::ObjectId, DateTime};
+use chrono::NaiveDate;
+use crate::models::dates;
+use crate::models::settings::RetirementSettings;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Employee {
@@ -30,13 +33,13 @@ pub struct Employee {
     // Identification
     #[serde(rename = "nicNumber")]
     pub nic_number: String,
-    #[serde(rename = "dateOfBirth")]
-    pub date_of_birth: String, // dd-MM-yyyy format
-    pub age: i32,
+    #[serde(rename = "dateOfBirth", with = "dates::date")]
+    pub date_of_birth: NaiveDate,
+    pub age: i32, // Derived from the date of birth
 
     // Employment Dates
-    #[serde(rename = "firstAppointmentDate")]
-    pub first_appointment_date: String, // dd-MM-yyyy
+    #[serde(rename = "firstAppointmentDate", with = "dates::date")]
+    pub first_appointment_date: NaiveDate,
     #[serde(rename = "gradeAppointmentDate")]
     pub grade_appointment_date: GradeAppointmentDates,
 
@@ -51,24 +54,34 @@ pub struct Employee {
     pub educational_qualification: String,
     #[serde(rename = "centralProvincial")]
     pub central_provincial: CentralProvincial,
-    #[serde(rename = "dateOfArrivalVDS")]
-    pub date_of_arrival_vds: String, // dd-MM-yyyy
-    pub status: String,
-    #[serde(rename = "dateOfTransfer", skip_serializing_if = "Option::is_none")]
-    pub date_of_transfer: Option<String>, // dd-MM-yyyy
+    #[serde(rename = "dateOfArrivalVDS", with = "dates::optional_date", default)]
+    pub date_of_arrival_vds: Option<NaiveDate>,
+    pub status: EmploymentStatus, // Changed through change_employment_status to keep the history
+    #[serde(rename = "dateOfTransfer", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub date_of_transfer: Option<NaiveDate>,
     #[serde(rename = "ebPass")]
     pub eb_pass: bool,
     #[serde(rename = "serviceConfirmed")]
     pub service_confirmed: bool,
     #[serde(rename = "secondLanguagePassed")]
     pub second_language_passed: bool,
-    #[serde(rename = "retiredDate")]
-    pub retired_date: String, // Auto-calculated
+    #[serde(rename = "retiredDate", with = "dates::optional_date", default)]
+    pub retired_date: Option<NaiveDate>, // Derived from the date of birth and service category
     #[serde(rename = "maritalStatus")]
     pub marital_status: MaritalStatus,
     #[serde(rename = "salaryCode")]
     pub salary_code: SalaryCode,
 
+    // Archive (soft delete) for staff who have left but must stay on record
+    #[serde(default)]
+    pub archived: bool,
+    #[serde(rename = "archivedAt", skip_serializing_if = "Option::is_none")]
+    pub archived_at: Option<DateTime>,
+    #[serde(rename = "archivedBy", skip_serializing_if = "Option::is_none")]
+    pub archived_by: Option<String>,
+    #[serde(rename = "archiveReason", skip_serializing_if = "Option::is_none")]
+    pub archive_reason: Option<String>,
+
     // Metadata
     #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
     pub created_at: Option<DateTime>,
@@ -76,6 +89,21 @@ pub struct Employee {
     pub updated_at: Option<DateTime>,
 }
 
+impl Employee {
+    /// Work out the age on `today` and the retirement date, replacing any stored values.
+    /// Retired staff keep the date they actually retired on.
+    pub fn derive_dates(&mut self, settings: &RetirementSettings, today: NaiveDate) {
+        self.age = dates::years_between(&self.date_of_birth, &today);
+        if self.status == EmploymentStatus::Retired && self.retired_date.is_some() {
+            return;
+        }
+        self.retired_date = Some(dates::add_years(
+            &self.date_of_birth,
+            settings.retirement_age(self.central_provincial)
+        ));
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Address {
     pub line1: String,
@@ -85,17 +113,17 @@ pub struct Address {
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GradeAppointmentDates {
-    #[serde(rename = "gradeIII", skip_serializing_if = "Option::is_none")]
-    pub grade_iii: Option<String>,
-    #[serde(rename = "gradeII", skip_serializing_if = "Option::is_none")]
-    pub grade_ii: Option<String>,
-    #[serde(rename = "gradeI", skip_serializing_if = "Option::is_none")]
-    pub grade_i: Option<String>,
-    #[serde(rename = "gradeSupra", skip_serializing_if = "Option::is_none")]
-    pub grade_supra: Option<String>,
+    #[serde(rename = "gradeIII", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub grade_iii: Option<NaiveDate>,
+    #[serde(rename = "gradeII", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub grade_ii: Option<NaiveDate>,
+    #[serde(rename = "gradeI", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub grade_i: Option<NaiveDate>,
+    #[serde(rename = "gradeSupra", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub grade_supra: Option<NaiveDate>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum Designation {
     #[serde(rename = "District Officer")]
     DistrictOfficer,
@@ -113,18 +141,116 @@ pub enum Designation {
     GardenLabour,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum Gender {
     Male,
     Female,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum CentralProvincial {
     Central,
     Provincial,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum EmploymentStatus {
+    #[serde(rename = "Active")]
+    Active,
+    #[serde(rename = "On Leave")]
+    OnLeave,
+    #[serde(rename = "Interdicted")]
+    Interdicted,
+    #[serde(rename = "Transferred Out")]
+    TransferredOut,
+    #[serde(rename = "Retired")]
+    Retired,
+    #[serde(rename = "Deceased")]
+    Deceased,
+    #[serde(rename = "Resigned")]
+    Resigned,
+    #[serde(rename = "Vacated Post")]
+    VacatedPost,
+}
+
+impl EmploymentStatus {
+    pub const ALL: [EmploymentStatus; 8] = [
+        EmploymentStatus::Active,
+        EmploymentStatus::OnLeave,
+        EmploymentStatus::Interdicted,
+        EmploymentStatus::TransferredOut,
+        EmploymentStatus::Retired,
+        EmploymentStatus::Deceased,
+        EmploymentStatus::Resigned,
+        EmploymentStatus::VacatedPost,
+    ];
+
+    /// Parse a status, accepting the free-text values stored before statuses were structured.
+    /// A missing status is read as active.
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized: String = value.to_lowercase()
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect();
+        match normalized.as_str() {
+            "" | "active" | "working" | "permanent" => Some(EmploymentStatus::Active),
+            "onleave" | "leave" => Some(EmploymentStatus::OnLeave),
+            "interdicted" | "interdiction" | "suspended" => Some(EmploymentStatus::Interdicted),
+            "transferredout" | "transferred" | "transfer" => Some(EmploymentStatus::TransferredOut),
+            "retired" | "retirement" => Some(EmploymentStatus::Retired),
+            "deceased" | "dead" | "died" => Some(EmploymentStatus::Deceased),
+            "resigned" | "resignation" => Some(EmploymentStatus::Resigned),
+            "vacatedpost" | "vacated" | "vop" => Some(EmploymentStatus::VacatedPost),
+            _ => None,
+        }
+    }
+
+    /// Check whether an employee may move from this status to `next`.
+    /// Retirement, death, resignation and vacation of post end the employment.
+    pub fn can_transition_to(&self, next: EmploymentStatus) -> bool {
+        use EmploymentStatus::*;
+        match self {
+            Active => next != Active,
+            OnLeave => next != OnLeave,
+            Interdicted => matches!(next, Active | Retired | Deceased | Resigned | VacatedPost),
+            TransferredOut => next == Active,
+            Retired | Deceased | Resigned | VacatedPost => false,
+        }
+    }
+}
+
+/// A dated change of an employee's employment status
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct EmploymentStatusChange {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "fromStatus")]
+    pub from_status: EmploymentStatus,
+    #[serde(rename = "toStatus")]
+    pub to_status: EmploymentStatus,
+    #[serde(rename = "effectiveDate")]
+    pub effective_date: String, // dd-MM-yyyy
+    #[serde(rename = "changedBy")]
+    pub changed_by: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChangeEmploymentStatusRequest {
+    pub status: EmploymentStatus,
+    #[serde(rename = "effectiveDate")]
+    pub effective_date: String, // dd-MM-yyyy
+    #[serde(rename = "changedBy")]
+    pub changed_by: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum MaritalStatus {
     Single,
@@ -156,6 +282,8 @@ pub struct EmployeeFilter {
     pub salary_code: Option<SalaryCode>,
     #[serde(rename = "ageRange", skip_serializing_if = "Option::is_none")]
     pub age_range: Option<AgeRange>,
+    #[serde(rename = "includeArchived", default)]
+    pub include_archived: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -164,6 +292,29 @@ pub struct AgeRange {
     pub max: i32,
 }
 
+/// What happens to an employee's attendance and leave when the employee is deleted
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DeletePolicy {
+    #[serde(rename = "block")]
+    Block, // Refuse to delete while dependent records exist
+    #[serde(rename = "cascade")]
+    Cascade, // Delete the dependent records
+    #[serde(rename = "archive")]
+    Archive, // Move the dependent records to the archive collections
+}
+
+/// An attendance record, leave application or ledger entry whose employee no longer exists
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrphanedRecord {
+    pub collection: String,
+    pub id: String,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber", skip_serializing_if = "Option::is_none")]
+    pub employee_number: Option<String>,
+    pub details: String,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CreateEmployeeRequest {
     #[serde(flatten)]
@@ -201,4 +352,100 @@ pub struct EmployeesResponse {
     pub message: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct OrphanedRecordsResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<OrphanedRecord>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A stored employee date that could not be read as dd-MM-yyyy during migration
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvalidEmployeeDate {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    pub field: String,
+    pub value: String,
+}
+
+/// A stored employment status that matches none of the structured statuses
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct InvalidEmployeeStatus {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    pub value: String,
+}
+
+/// An employee sharing a value that must be unique
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateEmployee {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "fullName")]
+    pub full_name: String,
+}
+
+/// Employees with the same employee number, NIC number or email address
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateEmployeeGroup {
+    pub field: String, // employeeNumber, nicNumber or emailAddress
+    pub value: String,
+    pub employees: Vec<DuplicateEmployee>,
+}
+
+/// Unique indexes that could not be built and the records keeping them from being built
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DuplicateEmployeeReport {
+    #[serde(rename = "indexErrors")]
+    pub index_errors: Vec<String>,
+    pub duplicates: Vec<DuplicateEmployeeGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DuplicateEmployeesResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<DuplicateEmployeeReport>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DateMigrationResponse {
+    pub success: bool,
+    #[serde(rename = "employeesUpdated")]
+    pub employees_updated: u64,
+    #[serde(rename = "invalidDates")]
+    pub invalid_dates: Vec<InvalidEmployeeDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StatusMigrationResponse {
+    pub success: bool,
+    #[serde(rename = "employeesUpdated")]
+    pub employees_updated: u64,
+    #[serde(rename = "invalidStatuses")]
+    pub invalid_statuses: Vec<InvalidEmployeeStatus>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
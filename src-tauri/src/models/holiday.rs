@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+use mongodb::bson::{oid::ObjectId, DateTime};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Holiday {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    pub date: String, // dd-MM-yyyy
+    pub name: String,
+    #[serde(rename = "holidayType")]
+    pub holiday_type: HolidayType,
+    // Derived from the date on the backend so holidays can be queried by month
+    #[serde(default)]
+    pub year: i32,
+    #[serde(default)]
+    pub month: i32,
+    #[serde(default)]
+    pub day: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum HolidayType {
+    #[serde(rename = "public")]
+    Public,
+    #[serde(rename = "bank")]
+    Bank,
+    #[serde(rename = "mercantile")]
+    Mercantile,
+    #[serde(rename = "poya")]
+    Poya,
+    #[serde(rename = "office-closure")]
+    OfficeClosure,
+}
+
+impl HolidayType {
+    /// Whether the office is closed on this kind of holiday.
+    /// Bank and mercantile holidays only apply to banks and the private sector.
+    pub fn closes_office(&self) -> bool {
+        matches!(self, HolidayType::Public | HolidayType::Poya | HolidayType::OfficeClosure)
+    }
+}
+
+/// Working day breakdown of a month
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WorkingDays {
+    pub month: i32,
+    pub year: i32,
+    #[serde(rename = "calendarDays")]
+    pub calendar_days: i32,
+    #[serde(rename = "weekendDays")]
+    pub weekend_days: i32,
+    pub holidays: Vec<Holiday>, // Office-closing holidays that fall on weekdays
+    #[serde(rename = "workingDays")]
+    pub working_days: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HolidayResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Holiday>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HolidayImportResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Vec<Holiday>>,
+    pub imported: u64,
+    pub skipped: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
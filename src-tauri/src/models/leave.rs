@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use mongodb::bson::{oid::ObjectId, DateTime};
+use chrono::{Datelike, NaiveDate};
+use super::dates::parse_date;
+use super::employee::{CentralProvincial, Designation};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveApplication {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "leaveType")]
+    pub leave_type: LeaveType,
+    #[serde(rename = "startDate")]
+    pub start_date: String, // dd-MM-yyyy
+    #[serde(rename = "endDate")]
+    pub end_date: String, // dd-MM-yyyy
+    #[serde(rename = "totalDays")]
+    pub total_days: f64, // Calculated on the backend
+    #[serde(rename = "isHalfDay")]
+    pub is_half_day: bool,
+    pub reason: String,
+    pub status: LeaveStatus,
+    #[serde(rename = "appliedDate")]
+    pub applied_date: String, // dd-MM-yyyy
+    #[serde(rename = "approvedBy", skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(rename = "approvedDate", skip_serializing_if = "Option::is_none")]
+    pub approved_date: Option<String>, // dd-MM-yyyy
+    #[serde(rename = "rejectedReason", skip_serializing_if = "Option::is_none")]
+    pub rejected_reason: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+impl LeaveApplication {
+    /// Parse the start and end dates of the application
+    pub fn date_range(&self) -> Option<(NaiveDate, NaiveDate)> {
+        let start = parse_date(&self.start_date)?;
+        let end = parse_date(&self.end_date)?;
+        if end < start {
+            return None;
+        }
+        Some((start, end))
+    }
+
+    /// Working days covered by the application. Weekends and holidays are not leave.
+    pub fn working_days(&self, is_working_day: impl Fn(NaiveDate) -> bool) -> Vec<NaiveDate> {
+        match self.date_range() {
+            Some((start, end)) => start.iter_days()
+                .take_while(|d| *d <= end)
+                .filter(|d| is_working_day(*d))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Number of leave days covered by the application (half days count as 0.5).
+    /// None when the dates are invalid or a half day spans more than one date.
+    pub fn calculate_total_days(&self, is_working_day: impl Fn(NaiveDate) -> bool) -> Option<f64> {
+        let (start, end) = self.date_range()?;
+        if self.is_half_day && start != end {
+            return None;
+        }
+        let days = self.working_days(is_working_day).len() as f64;
+        Some(if self.is_half_day { days * 0.5 } else { days })
+    }
+
+    /// Leave days split by calendar year, for applications spanning the new year
+    pub fn days_by_year(&self, is_working_day: impl Fn(NaiveDate) -> bool) -> Vec<(i32, f64)> {
+        let per_day = if self.is_half_day { 0.5 } else { 1.0 };
+
+        let mut result: Vec<(i32, f64)> = Vec::new();
+        for date in self.working_days(is_working_day) {
+            match result.last_mut() {
+                Some((year, days)) if *year == date.year() => *days += per_day,
+                _ => result.push((date.year(), per_day)),
+            }
+        }
+        result
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveType {
+    #[serde(rename = "casual-leave")]
+    CasualLeave,
+    #[serde(rename = "vacation-leave", alias = "annual-leave")]
+    VacationLeave,
+    #[serde(rename = "sick-leave")]
+    SickLeave,
+    #[serde(rename = "duty-leave")]
+    DutyLeave,
+    #[serde(rename = "maternity-leave")]
+    MaternityLeave,
+    #[serde(rename = "no-pay-leave")]
+    NoPayLeave,
+}
+
+impl LeaveType {
+    pub const ALL: [LeaveType; 6] = [
+        LeaveType::CasualLeave,
+        LeaveType::VacationLeave,
+        LeaveType::SickLeave,
+        LeaveType::DutyLeave,
+        LeaveType::MaternityLeave,
+        LeaveType::NoPayLeave,
+    ];
+
+    /// Whether leave of this type is drawn from the yearly entitlement.
+    /// Duty and no-pay leave are not limited by a balance.
+    pub fn is_limited(&self) -> bool {
+        !matches!(self, LeaveType::DutyLeave | LeaveType::NoPayLeave)
+    }
+
+    /// Yearly entitlement used when no entitlement is configured
+    pub fn default_entitlement(&self) -> f64 {
+        match self {
+            LeaveType::CasualLeave => 7.0,
+            LeaveType::VacationLeave => 24.0,
+            LeaveType::SickLeave => 21.0,
+            LeaveType::DutyLeave => 0.0,
+            LeaveType::MaternityLeave => 84.0,
+            LeaveType::NoPayLeave => 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LeaveStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "approved")]
+    Approved,
+    #[serde(rename = "rejected")]
+    Rejected,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+}
+
+impl LeaveStatus {
+    /// Check whether an application may move from this status to `next`
+    pub fn can_transition_to(&self, next: LeaveStatus) -> bool {
+        matches!(
+            (self, next),
+            (LeaveStatus::Pending, LeaveStatus::Approved)
+                | (LeaveStatus::Pending, LeaveStatus::Rejected)
+                | (LeaveStatus::Pending, LeaveStatus::Cancelled)
+                | (LeaveStatus::Approved, LeaveStatus::Rejected)
+                | (LeaveStatus::Approved, LeaveStatus::Cancelled)
+        )
+    }
+}
+
+/// Yearly leave entitlement, optionally scoped to a designation and/or service
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveEntitlement {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub designation: Option<Designation>,
+    #[serde(rename = "centralProvincial", skip_serializing_if = "Option::is_none")]
+    pub central_provincial: Option<CentralProvincial>,
+    #[serde(rename = "leaveType")]
+    pub leave_type: LeaveType,
+    pub days: f64,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+impl LeaveEntitlement {
+    /// How specifically this entitlement matches an employee, or None if it does not apply
+    pub fn specificity(&self, designation: Designation, central_provincial: CentralProvincial) -> Option<u8> {
+        let mut score = 0;
+        if let Some(d) = self.designation {
+            if d != designation {
+                return None;
+            }
+            score += 2;
+        }
+        if let Some(cp) = self.central_provincial {
+            if cp != central_provincial {
+                return None;
+            }
+            score += 1;
+        }
+        Some(score)
+    }
+}
+
+/// Entry in the leave balance ledger
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveLedgerEntry {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<ObjectId>,
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    pub year: i32,
+    #[serde(rename = "leaveType")]
+    pub leave_type: LeaveType,
+    #[serde(rename = "entryType")]
+    pub entry_type: LedgerEntryType,
+    pub days: f64, // Always positive, the entry type gives the direction
+    #[serde(rename = "leaveApplicationId", skip_serializing_if = "Option::is_none")]
+    pub leave_application_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(rename = "createdAt", skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerEntryType {
+    #[serde(rename = "opening")]
+    Opening, // Balance brought forward into the year
+    #[serde(rename = "debit")]
+    Debit, // Leave taken on approval
+    #[serde(rename = "credit")]
+    Credit, // Leave returned on cancellation
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveBalance {
+    #[serde(rename = "leaveType")]
+    pub leave_type: LeaveType,
+    pub entitled: f64,
+    pub taken: f64,
+    pub pending: f64,
+    pub remaining: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EmployeeLeaveBalance {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    pub year: i32,
+    pub balances: Vec<LeaveBalance>,
+}
+
+/// Vacation leave carried from one year into the next for an employee
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveRolloverEntry {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "employeeNumber")]
+    pub employee_number: String,
+    #[serde(rename = "fullName")]
+    pub full_name: String,
+    pub unused: f64,
+    #[serde(rename = "carriedForward")]
+    pub carried_forward: f64,
+    pub lapsed: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LeaveRolloverReport {
+    #[serde(rename = "fromYear")]
+    pub from_year: i32,
+    #[serde(rename = "toYear")]
+    pub to_year: i32,
+    #[serde(rename = "carryForwardCap")]
+    pub carry_forward_cap: f64,
+    pub entries: Vec<LeaveRolloverEntry>,
+    #[serde(rename = "totalCarriedForward")]
+    pub total_carried_forward: f64,
+    #[serde(rename = "totalLapsed")]
+    pub total_lapsed: f64,
+}
+
+/// Leave details entered when applying. The backend fills in the rest.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NewLeaveApplication {
+    #[serde(rename = "employeeId")]
+    pub employee_id: String,
+    #[serde(rename = "leaveType")]
+    pub leave_type: LeaveType,
+    #[serde(rename = "startDate")]
+    pub start_date: String, // dd-MM-yyyy
+    #[serde(rename = "endDate")]
+    pub end_date: String, // dd-MM-yyyy
+    #[serde(rename = "isHalfDay", default)]
+    pub is_half_day: bool,
+    pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateLeaveRequest {
+    #[serde(rename = "leaveApplication")]
+    pub leave_application: NewLeaveApplication,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateLeaveRequest {
+    pub status: LeaveStatus,
+    #[serde(rename = "approvedBy", skip_serializing_if = "Option::is_none")]
+    pub approved_by: Option<String>,
+    #[serde(rename = "rejectedReason", skip_serializing_if = "Option::is_none")]
+    pub rejected_reason: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<LeaveApplication>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveBalanceResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<EmployeeLeaveBalance>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveEntitlementResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<LeaveEntitlement>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LeaveRolloverResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<LeaveRolloverReport>,
+    #[serde(rename = "filePath", skip_serializing_if = "Option::is_none")]
+    pub file_path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -1,14 +1,54 @@
 pub mod employee;
 pub mod attendance;
+pub mod leave;
+pub mod holiday;
+pub mod settings;
+pub mod biometric;
+pub mod anomaly;
+pub mod dashboard;
+pub mod notification;
+pub mod dates;
+pub mod nic;
 
 // Use specific exports instead of glob exports to avoid naming conflicts
 pub use employee::{
     Employee, EmployeeFilter, EmployeeResponse, EmployeesResponse,
     CreateEmployeeRequest, UpdateEmployeeRequest, Address, GradeAppointmentDates,
-    Designation, Gender, CentralProvincial, MaritalStatus, SalaryCode, AgeRange
+    Designation, Gender, CentralProvincial, MaritalStatus, SalaryCode, AgeRange, DeletePolicy,
+    OrphanedRecord, OrphanedRecordsResponse, EmploymentStatus, EmploymentStatusChange,
+    ChangeEmploymentStatusRequest, InvalidEmployeeDate, DateMigrationResponse, DuplicateEmployee,
+    DuplicateEmployeeGroup, DuplicateEmployeeReport, DuplicateEmployeesResponse, InvalidEmployeeStatus,
+    StatusMigrationResponse
 };
 
 pub use attendance::{
     AttendanceRecord, DailyAttendance, AttendanceStatus, MonthlyAttendanceSummary, AttendanceFilter,
-    CreateAttendanceRequest, UpdateAttendanceRequest, AttendanceResponse, MonthlySummaryResponse
-};
\ No newline at end of file
+    CreateAttendanceRequest, UpdateAttendanceRequest, AttendanceResponse, MonthlySummaryResponse,
+    BulkAttendanceEntry, BulkAttendanceResult, BulkAttendanceResponse, AttendanceLock, AttendanceLockEvent,
+    AttendanceLockResponse, AttendanceTotals, MonthAttendanceTotals, EmployeeAttendanceHistory,
+    YearlySummaryResponse, AttendanceHistoryResponse, EmployeeNumberSyncResponse
+};
+
+pub use leave::{
+    LeaveApplication, LeaveType, LeaveStatus, CreateLeaveRequest, UpdateLeaveRequest, LeaveResponse,
+    LeaveEntitlement, LeaveLedgerEntry, LedgerEntryType, LeaveBalance, EmployeeLeaveBalance,
+    LeaveBalanceResponse, LeaveEntitlementResponse, LeaveRolloverEntry, LeaveRolloverReport,
+    LeaveRolloverResponse
+};
+
+pub use holiday::{
+    Holiday, HolidayType, WorkingDays, HolidayResponse, HolidayImportResponse
+};
+
+pub use settings::{
+    AttendanceSettings, AttendanceSettingsResponse, RetirementSettings, RetirementSettingsResponse
+};
+pub use biometric::{
+    PunchLogFormat, PunchLogColumns, PunchLogImportOptions, PunchDirection, ImportedAttendanceDay,
+    PunchLogImportResult, PunchLogImportResponse
+};
+
+pub use anomaly::{AnomalyType, AttendanceAnomaly, AnomalyReport, AnomalyReportResponse};
+pub use dashboard::{UpcomingEventType, UpcomingEvent, UpcomingEventCount, UpcomingEvents, UpcomingEventsResponse};
+pub use notification::{NotificationState, DeadlineAlert, NotificationResponse};
+pub use nic::{Nic, NicFormat};
@@ -0,0 +1,232 @@
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
+use crate::models::employee::Gender;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum NicFormat {
+    #[serde(rename = "old")]
+    Old, // YY DDD SSS C followed by V or X
+    #[serde(rename = "new")]
+    New, // YYYY DDD SSSS C
+}
+
+/// A decoded National Identity Card number
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Nic {
+    pub number: String, // Without spaces, letter in upper case
+    pub format: NicFormat,
+    #[serde(rename = "birthYear")]
+    pub birth_year: i32,
+    #[serde(rename = "dayOfYear")]
+    pub day_of_year: u32, // With the 500 added for women removed
+    pub gender: Gender,
+    #[serde(rename = "serialNumber")]
+    pub serial_number: String,
+    #[serde(rename = "checkDigit")]
+    pub check_digit: char,
+}
+
+impl Nic {
+    /// Validate and decode an old or new format NIC number
+    pub fn parse(value: &str) -> Result<Nic, String> {
+        let number: String = value.chars()
+            .filter(|c| !c.is_whitespace())
+            .collect::<String>()
+            .to_uppercase();
+
+        let (format, birth_year, day, serial_number, check_digit) = if is_old_format(&number) {
+            // Old numbers were issued to people born in the 1900s
+            (NicFormat::Old, 1900 + digits(&number[0..2]), digits(&number[2..5]), &number[5..8], &number[8..9])
+        } else if number.len() == 12 && number.chars().all(|c| c.is_ascii_digit()) {
+            (NicFormat::New, digits(&number[0..4]), digits(&number[4..7]), &number[7..11], &number[11..12])
+        } else {
+            return Err("Invalid NIC number. Use 9 digits followed by V or X, or 12 digits".to_string());
+        };
+
+        let (day_of_year, gender) = if day > 500 {
+            (day - 500, Gender::Female)
+        } else {
+            (day, Gender::Male)
+        };
+
+        let nic = Nic {
+            format,
+            birth_year,
+            day_of_year: day_of_year as u32,
+            gender,
+            serial_number: serial_number.to_string(),
+            check_digit: check_digit.chars().next().unwrap_or('0'),
+            number,
+        };
+
+        if nic.birth_date().is_none() {
+            return Err(format!("Invalid NIC number. Day {} is not a date in {}", day_of_year, birth_year));
+        }
+
+        Ok(nic)
+    }
+
+    /// The date of birth encoded in the number. Days are counted as in a leap year,
+    /// so day 60 is 29 February and 1 March is always day 61.
+    pub fn birth_date(&self) -> Option<NaiveDate> {
+        let day = NaiveDate::from_yo_opt(2000, self.day_of_year)?;
+        NaiveDate::from_ymd_opt(self.birth_year, day.month(), day.day())
+    }
+
+    /// Whether a date of birth agrees with the number. Dates worked out by counting
+    /// the days of the actual year, as older records were, are also accepted.
+    pub fn matches_birth_date(&self, date_of_birth: NaiveDate) -> bool {
+        self.birth_date() == Some(date_of_birth)
+            || NaiveDate::from_yo_opt(self.birth_year, self.day_of_year) == Some(date_of_birth)
+    }
+
+    /// The number in the 12 digit format, converting an old format number
+    pub fn to_new_format(&self) -> String {
+        match self.format {
+            NicFormat::New => self.number.clone(),
+            NicFormat::Old => format!(
+                "{}{}0{}{}",
+                self.birth_year,
+                &self.number[2..5],
+                self.serial_number,
+                self.check_digit
+            ),
+        }
+    }
+}
+
+/// Check an employee's NIC number and that their date of birth and gender agree with it.
+/// Returns the number in the 12 digit format, so both forms of a number are stored alike.
+pub fn validate_employee_nic(nic_number: &str, date_of_birth: NaiveDate, gender: Gender) -> Result<String, String> {
+    let nic = Nic::parse(nic_number)?;
+
+    if !nic.matches_birth_date(date_of_birth) {
+        return Err(format!(
+            "Date of birth does not match the NIC number, which gives {}",
+            nic.birth_date().map(|d| crate::models::dates::format_date(&d)).unwrap_or_default()
+        ));
+    }
+
+    if nic.gender != gender {
+        return Err(format!("Gender does not match the NIC number, which gives {:?}", nic.gender));
+    }
+
+    Ok(nic.to_new_format())
+}
+
+fn is_old_format(number: &str) -> bool {
+    number.len() == 10
+        && number.is_ascii()
+        && number[0..9].chars().all(|c| c.is_ascii_digit())
+        && matches!(&number[9..], "V" | "X")
+}
+
+fn digits(value: &str) -> i32 {
+    value.parse().unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn parses_old_format() {
+        let nic = Nic::parse(" 853400123v ").unwrap();
+        assert_eq!(nic.number, "853400123V");
+        assert_eq!(nic.format, NicFormat::Old);
+        assert_eq!(nic.birth_year, 1985);
+        assert_eq!(nic.day_of_year, 340);
+        assert_eq!(nic.gender, Gender::Male);
+        assert_eq!(nic.serial_number, "012");
+        assert_eq!(nic.check_digit, '3');
+        assert_eq!(nic.birth_date(), Some(date(1985, 12, 5)));
+    }
+
+    #[test]
+    fn parses_new_format() {
+        let nic = Nic::parse("198534000123").unwrap();
+        assert_eq!(nic.format, NicFormat::New);
+        assert_eq!(nic.birth_year, 1985);
+        assert_eq!(nic.day_of_year, 340);
+        assert_eq!(nic.gender, Gender::Male);
+        assert_eq!(nic.serial_number, "0012");
+        assert_eq!(nic.check_digit, '3');
+        assert_eq!(nic.birth_date(), Some(date(1985, 12, 5)));
+    }
+
+    #[test]
+    fn rejects_malformed_numbers() {
+        assert!(Nic::parse("85340012V").is_err());
+        assert!(Nic::parse("853400123A").is_err());
+        assert!(Nic::parse("19853400012").is_err());
+        assert!(Nic::parse("85340012-V").is_err());
+        assert!(Nic::parse("").is_err());
+    }
+
+    #[test]
+    fn women_have_500_added_to_the_day() {
+        let old = Nic::parse("858400123V").unwrap();
+        assert_eq!(old.gender, Gender::Female);
+        assert_eq!(old.day_of_year, 340);
+        assert_eq!(old.birth_date(), Some(date(1985, 12, 5)));
+
+        let new = Nic::parse("198584000123").unwrap();
+        assert_eq!(new.gender, Gender::Female);
+        assert_eq!(new.birth_date(), Some(date(1985, 12, 5)));
+    }
+
+    #[test]
+    fn days_are_counted_as_in_a_leap_year() {
+        assert_eq!(Nic::parse("840600123V").unwrap().birth_date(), Some(date(1984, 2, 29)));
+        assert_eq!(Nic::parse("850610123V").unwrap().birth_date(), Some(date(1985, 3, 1)));
+        assert_eq!(Nic::parse("850010123V").unwrap().birth_date(), Some(date(1985, 1, 1)));
+        assert_eq!(Nic::parse("853660123V").unwrap().birth_date(), Some(date(1985, 12, 31)));
+    }
+
+    #[test]
+    fn day_60_is_not_a_date_in_a_non_leap_year() {
+        assert!(Nic::parse("850600123V").is_err());
+        assert!(Nic::parse("198506000123").is_err());
+        assert!(Nic::parse("855600123V").is_err());
+    }
+
+    #[test]
+    fn rejects_days_outside_the_year() {
+        assert!(Nic::parse("850000123V").is_err());
+        assert!(Nic::parse("853670123V").is_err());
+        assert!(Nic::parse("855000123V").is_err());
+    }
+
+    #[test]
+    fn accepts_birth_dates_counted_in_the_actual_year() {
+        let nic = Nic::parse("850610123V").unwrap();
+        assert!(nic.matches_birth_date(date(1985, 3, 1)));
+        assert!(nic.matches_birth_date(date(1985, 3, 2)));
+        assert!(!nic.matches_birth_date(date(1985, 3, 3)));
+    }
+
+    #[test]
+    fn converts_to_new_format() {
+        assert_eq!(Nic::parse("853400123V").unwrap().to_new_format(), "198534000123");
+        assert_eq!(Nic::parse("858400123X").unwrap().to_new_format(), "198584000123");
+        assert_eq!(Nic::parse("198534000123").unwrap().to_new_format(), "198534000123");
+
+        let converted = Nic::parse(&Nic::parse("853400123V").unwrap().to_new_format()).unwrap();
+        assert_eq!(converted.birth_date(), Some(date(1985, 12, 5)));
+        assert_eq!(converted.gender, Gender::Male);
+    }
+
+    #[test]
+    fn validates_employee_details_against_the_number() {
+        assert_eq!(
+            validate_employee_nic("853400123v", date(1985, 12, 5), Gender::Male),
+            Ok("198534000123".to_string())
+        );
+        assert!(validate_employee_nic("853400123V", date(1985, 12, 7), Gender::Male).is_err());
+        assert!(validate_employee_nic("853400123V", date(1985, 12, 5), Gender::Female).is_err());
+    }
+}
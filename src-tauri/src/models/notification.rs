@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use mongodb::bson::DateTime;
+use chrono::NaiveDate;
+use crate::models::dashboard::{UpcomingEvent, UpcomingEventType};
+use crate::models::dates;
+
+/// Whether a deadline alert was shown, dismissed or snoozed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct NotificationState {
+    #[serde(rename = "_id")]
+    pub key: String, // See DeadlineAlert::key
+    #[serde(default)]
+    pub dismissed: bool,
+    #[serde(rename = "snoozedUntil", skip_serializing_if = "Option::is_none")]
+    pub snoozed_until: Option<DateTime>,
+    #[serde(rename = "notifiedAt", skip_serializing_if = "Option::is_none")]
+    pub notified_at: Option<DateTime>,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+/// An upcoming HR deadline that has not been dismissed or snoozed
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DeadlineAlert {
+    pub key: String,
+    #[serde(flatten)]
+    pub event: UpcomingEvent,
+    #[serde(rename = "notifiedAt", with = "dates::optional_date", default, skip_serializing_if = "Option::is_none")]
+    pub notified_at: Option<NaiveDate>, // Day the alert was first raised
+}
+
+impl DeadlineAlert {
+    /// The key of an event's alert. It includes the date, so a yearly increment
+    /// is alerted again the next year after being dismissed.
+    pub fn key(event: &UpcomingEvent) -> String {
+        let event_type = match event.event_type {
+            UpcomingEventType::Retirement => "retirement",
+            UpcomingEventType::Increment => "increment",
+            UpcomingEventType::ServiceConfirmation => "service-confirmation",
+            UpcomingEventType::EbExam => "eb-exam",
+            UpcomingEventType::SecondLanguage => "second-language",
+            UpcomingEventType::Birthday => "birthday",
+        };
+        format!("{}:{}:{}", event_type, event.employee_id, event.date)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NotificationResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,118 @@
+use serde::{Deserialize, Serialize};
+use mongodb::bson::DateTime;
+use chrono::NaiveTime;
+use crate::models::employee::CentralProvincial;
+
+/// Time format used for arrival and departure times (HH:mm, 24 hour)
+pub const TIME_FORMAT: &str = "%H:%M";
+
+/// Parse a HH:mm time string
+pub fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value.trim(), TIME_FORMAT).ok()
+}
+
+/// Office hours and short leave rules used to evaluate daily attendance
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AttendanceSettings {
+    #[serde(rename = "officeStart")]
+    pub office_start: String, // HH:mm
+    #[serde(rename = "officeEnd")]
+    pub office_end: String, // HH:mm
+    #[serde(rename = "graceMinutes")]
+    pub grace_minutes: i64, // Minutes after the start time before an arrival is late
+    #[serde(rename = "shortLeavesPerMonth")]
+    pub short_leaves_per_month: i32,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+impl Default for AttendanceSettings {
+    fn default() -> Self {
+        Self {
+            office_start: "08:30".to_string(),
+            office_end: "16:15".to_string(),
+            grace_minutes: 0,
+            short_leaves_per_month: 2,
+            updated_at: None,
+        }
+    }
+}
+
+impl AttendanceSettings {
+    /// Check that the office hours are valid times with the start before the end
+    pub fn validate(&self) -> Result<(), String> {
+        let start = parse_time(&self.office_start)
+            .ok_or_else(|| "Invalid office start time. Use HH:mm".to_string())?;
+        let end = parse_time(&self.office_end)
+            .ok_or_else(|| "Invalid office end time. Use HH:mm".to_string())?;
+
+        if end <= start {
+            return Err("Office end time must be after the start time".to_string());
+        }
+        if self.grace_minutes < 0 || self.short_leaves_per_month < 0 {
+            return Err("Grace minutes and short leaves cannot be negative".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Age at which employees retire, by service category
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct RetirementSettings {
+    #[serde(rename = "centralRetirementAge")]
+    pub central_retirement_age: i32,
+    #[serde(rename = "provincialRetirementAge")]
+    pub provincial_retirement_age: i32,
+    #[serde(rename = "updatedAt", skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<DateTime>,
+}
+
+impl Default for RetirementSettings {
+    fn default() -> Self {
+        Self {
+            central_retirement_age: 60,
+            provincial_retirement_age: 60,
+            updated_at: None,
+        }
+    }
+}
+
+impl RetirementSettings {
+    pub fn retirement_age(&self, category: CentralProvincial) -> i32 {
+        match category {
+            CentralProvincial::Central => self.central_retirement_age,
+            CentralProvincial::Provincial => self.provincial_retirement_age,
+        }
+    }
+
+    /// Check that the retirement ages are within working age
+    pub fn validate(&self) -> Result<(), String> {
+        let valid = |age: i32| (18..=100).contains(&age);
+        if !valid(self.central_retirement_age) || !valid(self.provincial_retirement_age) {
+            return Err("Retirement ages must be between 18 and 100".to_string());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RetirementSettingsResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<RetirementSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttendanceSettingsResponse {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<AttendanceSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
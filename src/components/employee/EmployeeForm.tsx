@@ -83,7 +83,7 @@ const EmployeeForm: React.FC = () => {
     educationalQualification: '',
     centralProvincial: 'Central',
     dateOfArrivalVDS: '',
-    status: '',
+    status: 'Active',
     dateOfTransfer: '',
     ebPass: false,
     serviceConfirmed: false,
@@ -683,11 +683,11 @@ const EmployeeForm: React.FC = () => {
                 <div className="grid grid-cols-2 gap-4">
                   <div>
                     <Label htmlFor="status">Status</Label>
+                    {/* Changed through the employment status history, not this form */}
                     <Input
                       id="status"
                       value={formData.status}
-                      onChange={(e) => handleFieldChange('status', e.target.value)}
-                      placeholder="Active, Inactive, etc."
+                      readOnly
                     />
                   </div>
 
@@ -2,6 +2,7 @@ import { useQuery, useMutation, useQueryClient } from '@tanstack/react-query';
 import {
   AttendanceRecord,
   LeaveApplication,
+  NewLeaveApplication,
   MonthlyAttendanceSummary,
   AttendanceFilter,
   AttendanceStatus,
@@ -51,6 +52,8 @@ export const attendanceApi = {
 
   updateAttendanceRecord: async (request: {
     employeeId: string;
+    month: number;
+    year: number;
     date: number;
     status: AttendanceStatus;
     notes?: string;
@@ -70,7 +73,7 @@ export const attendanceApi = {
     return await invoke('get_leave_applications', { employeeId, status });
   },
 
-  createLeaveApplication: async (leaveApplication: LeaveApplication): Promise<ApiResponse<LeaveApplication>> => {
+  createLeaveApplication: async (leaveApplication: NewLeaveApplication): Promise<ApiResponse<LeaveApplication>> => {
     return await invoke('create_leave_application', { request: { leaveApplication } });
   },
 
@@ -227,10 +230,10 @@ export const useAttendanceStats = (month: number, year: number) => {
 
   const stats = summaries.data.reduce(
     (acc, summary) => {
-      acc.totalPresent += summary.totalPresent;
-      acc.totalAbsent += summary.totalAbsent;
-      acc.totalLeaves += summary.totalLeaves;
-      acc.totalHalfDays += summary.totalHalfDays;
+      acc.totalPresent += summary.statusCounts['present'] ?? 0;
+      acc.totalAbsent += summary.statusCounts['absent'] ?? 0;
+      acc.totalLeaves += summary.totalLeaveDays;
+      acc.totalHalfDays += summary.statusCounts['half-day'] ?? 0;
       acc.attendanceRate += summary.attendancePercentage;
       return acc;
     },
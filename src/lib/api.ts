@@ -1,6 +1,6 @@
 import { invoke } from '@tauri-apps/api/tauri';
 import { Employee, EmployeeFilter } from '../types/employee';
-import { AttendanceRecord, LeaveApplication, AttendanceFilter } from '../types/attendance';
+import { AttendanceRecord, LeaveApplication, NewLeaveApplication, AttendanceFilter } from '../types/attendance';
 import { ApiResponse, PaginatedResponse, PrintOptions, ExportOptions } from '../types/common';
 
 // Additional types for better type safety
@@ -170,6 +170,8 @@ export const attendanceApi = {
    */
   async updateAttendanceRecord(request: {
     employeeId: string;
+    month: number;
+    year: number;
     date: number;
     status: string;
     notes?: string;
@@ -232,7 +234,7 @@ export const leaveApi = {
    * Create leave application
    */
   async createLeaveApplication(
-    leaveApplication: LeaveApplication
+    leaveApplication: NewLeaveApplication
   ): Promise<ApiResponse<LeaveApplication>> {
     try {
       const response = await invoke('create_leave_application', {
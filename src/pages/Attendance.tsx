@@ -30,9 +30,9 @@ const Attendance: React.FC = () => {
     ? Math.round(monthlySummaries.reduce((acc, summary) => acc + summary.attendancePercentage, 0) / monthlySummaries.length)
     : 0;
 
-  const totalPresent = monthlySummaries.reduce((acc, summary) => acc + summary.totalPresent, 0);
-  const totalAbsent = monthlySummaries.reduce((acc, summary) => acc + summary.totalAbsent, 0);
-  const totalHalfDays = monthlySummaries.reduce((acc, summary) => acc + summary.totalHalfDays, 0);
+  const totalPresent = monthlySummaries.reduce((acc, summary) => acc + (summary.statusCounts['present'] ?? 0), 0);
+  const totalAbsent = monthlySummaries.reduce((acc, summary) => acc + (summary.statusCounts['absent'] ?? 0), 0);
+  const totalHalfDays = monthlySummaries.reduce((acc, summary) => acc + (summary.statusCounts['half-day'] ?? 0), 0);
 
   // This was previously unused — now wired to UI below
   const handleBackupData = () => {
@@ -31,9 +31,9 @@ const Dashboard: React.FC = () => {
 
   // Attendance statistics for current month
   const currentMonthAttendance = monthlySummaries.reduce((acc, summary) => {
-    acc.totalPresent += summary.totalPresent;
-    acc.totalAbsent += summary.totalAbsent;
-    acc.totalHalfDays += summary.totalHalfDays;
+    acc.totalPresent += summary.statusCounts['present'] ?? 0;
+    acc.totalAbsent += summary.statusCounts['absent'] ?? 0;
+    acc.totalHalfDays += summary.statusCounts['half-day'] ?? 0;
     return acc;
   }, { totalPresent: 0, totalAbsent: 0, totalHalfDays: 0 });
 
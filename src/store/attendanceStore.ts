@@ -2,6 +2,12 @@ import { create } from 'zustand';
 import { AttendanceRecord, DailyAttendance, MonthlyAttendanceSummary, AttendanceStatus } from '../types/attendance';
 import { getCurrentMonthYear } from '../lib/dateUtils';
 
+// Every status, so the summary counts include zeroes like the backend summary
+const ATTENDANCE_STATUSES: AttendanceStatus[] = [
+  'present', 'absent', 'half-day', 'official-duty', 'leave', 'half-day-leave',
+  'casual-leave', 'sick-leave', 'no-pay', 'holiday', 'weekend'
+];
+
 interface AttendanceState {
   attendanceRecords: AttendanceRecord[];
   selectedMonth: number;
@@ -143,13 +149,20 @@ export const useAttendanceStore = create<AttendanceState>((set, get) => {
       const record = get().getAttendanceForEmployee(employeeId, month, year);
       if (!record) return null;
 
-      const totalWorkingDays = new Date(year, month, 0).getDate(); // Days in month
-      const totalPresent = record.records.filter(r => r.status === 'present').length;
-      const totalAbsent = record.records.filter(r => r.status === 'absent').length;
-      const totalHalfDays = record.records.filter(r => r.status === 'half-day').length;
+      const statusCounts = ATTENDANCE_STATUSES.reduce((counts, status) => {
+        counts[status] = record.records.filter(r => r.status === status).length;
+        return counts;
+      }, {} as Record<AttendanceStatus, number>);
+
+      const totalWorkingDays = new Date(year, month, 0).getDate()
+        - statusCounts['holiday'] - statusCounts['weekend'];
+      const totalAttendedDays = statusCounts['present'] + statusCounts['official-duty']
+        + (statusCounts['half-day'] + statusCounts['half-day-leave']) * 0.5;
+      const totalLeaveDays = statusCounts['leave'] + statusCounts['casual-leave']
+        + statusCounts['sick-leave'] + statusCounts['half-day-leave'] * 0.5;
 
       const attendancePercentage = totalWorkingDays > 0
-        ? Math.round(((totalPresent + (totalHalfDays * 0.5)) / totalWorkingDays) * 100)
+        ? Math.round((totalAttendedDays / totalWorkingDays) * 100)
         : 0;
 
       return {
@@ -159,9 +172,13 @@ export const useAttendanceStore = create<AttendanceState>((set, get) => {
         month,
         year,
         totalWorkingDays,
-        totalPresent,
-        totalAbsent,
-        totalHalfDays,
+        statusCounts,
+        totalAttendedDays,
+        totalLeaveDays,
+        lateArrivals: record.records.filter(r => r.isLate).length,
+        earlyDepartures: record.records.filter(r => r.isEarlyDeparture).length,
+        shortLeavesTaken: record.records.filter(r => r.shortLeave).length,
+        shortLeaveExcess: 0, // The allowance is applied by the backend summary
         attendancePercentage
       };
     },
@@ -13,12 +13,27 @@ export interface DailyAttendance {
   date: number; // Day of month (1-31)
   status: AttendanceStatus;
   notes?: string;
+  leaveApplicationId?: string; // Set when marked by approved leave
+  previousStatus?: AttendanceStatus;
+  checkIn?: string; // HH:mm
+  checkOut?: string; // HH:mm
+  isLate?: boolean; // Calculated on the backend
+  isEarlyDeparture?: boolean; // Calculated on the backend
+  shortLeave?: boolean;
 }
 
 export type AttendanceStatus =
   | 'present'
   | 'absent'
-  | 'half-day';
+  | 'half-day'
+  | 'official-duty'
+  | 'leave'
+  | 'half-day-leave'
+  | 'casual-leave'
+  | 'sick-leave'
+  | 'no-pay'
+  | 'holiday'
+  | 'weekend';
 
 export interface MonthlyAttendanceSummary {
   employeeId: string;
@@ -27,12 +42,55 @@ export interface MonthlyAttendanceSummary {
   month: number;
   year: number;
   totalWorkingDays: number;
-  totalPresent: number;
-  totalAbsent: number;
-  totalHalfDays: number;
+  statusCounts: Record<AttendanceStatus, number>;
+  totalAttendedDays: number;
+  totalLeaveDays: number;
+  lateArrivals: number;
+  earlyDepartures: number;
+  shortLeavesTaken: number;
+  shortLeaveExcess: number;
   attendancePercentage: number;
 }
 
+export type LeaveType =
+  | 'casual-leave'
+  | 'vacation-leave'
+  | 'sick-leave'
+  | 'duty-leave'
+  | 'maternity-leave'
+  | 'no-pay-leave';
+
+export type LeaveStatus = 'pending' | 'approved' | 'rejected' | 'cancelled';
+
+export interface LeaveApplication {
+  _id?: string;
+  employeeId: string;
+  employeeNumber: string;
+  leaveType: LeaveType;
+  startDate: string; // dd-MM-yyyy
+  endDate: string; // dd-MM-yyyy
+  totalDays: number; // Working days, calculated on the backend
+  isHalfDay: boolean;
+  reason: string;
+  status: LeaveStatus;
+  appliedDate: string; // dd-MM-yyyy
+  approvedBy?: string;
+  approvedDate?: string;
+  rejectedReason?: string;
+  createdAt?: Date;
+  updatedAt?: Date;
+}
+
+// Details entered when applying; the backend fills in the rest
+export interface NewLeaveApplication {
+  employeeId: string;
+  leaveType: LeaveType;
+  startDate: string; // dd-MM-yyyy
+  endDate: string; // dd-MM-yyyy
+  isHalfDay?: boolean;
+  reason: string;
+}
+
 export interface AttendanceFilter {
   employeeNumber?: string;
   month?: number;
@@ -40,4 +98,5 @@ export interface AttendanceFilter {
   status?: AttendanceStatus;
   designation?: string;
   department?: string;
+  matchingDaysOnly?: boolean;
 }
\ No newline at end of file
@@ -23,7 +23,7 @@ export interface Employee {
   // Identification
   nicNumber: string; // Can be old or new format
   dateOfBirth: string; // dd-MM-yyyy format
-  age: number; // Auto-calculated
+  age: number; // Derived by the backend from dateOfBirth
 
   // Employment Dates
   firstAppointmentDate: string; // dd-MM-yyyy
@@ -41,15 +41,21 @@ export interface Employee {
   educationalQualification: string;
   centralProvincial: 'Central' | 'Provincial';
   dateOfArrivalVDS: string; // dd-MM-yyyy
-  status: string;
+  status: EmploymentStatus; // Legacy free text is read as 'Active'
   dateOfTransfer?: string; // dd-MM-yyyy
   ebPass: boolean;
   serviceConfirmed: boolean;
   secondLanguagePassed: boolean;
-  retiredDate: string; // Auto-calculated (age 60)
+  retiredDate: string; // Derived by the backend from dateOfBirth and the retirement settings
   maritalStatus: 'Single' | 'Married' | 'Divorced' | 'Widowed';
   salaryCode: SalaryCode;
 
+  // Archive (soft delete)
+  archived?: boolean;
+  archivedAt?: Date;
+  archivedBy?: string;
+  archiveReason?: string;
+
   // Metadata
   createdAt?: Date;
   updatedAt?: Date;
@@ -64,6 +70,27 @@ export type Designation =
   | 'Office employee service'
   | 'Garden labour';
 
+export type EmploymentStatus =
+  | 'Active'
+  | 'On Leave'
+  | 'Interdicted'
+  | 'Transferred Out'
+  | 'Retired'
+  | 'Deceased'
+  | 'Resigned'
+  | 'Vacated Post';
+
+export interface EmploymentStatusChange {
+  _id?: string;
+  employeeId: string;
+  fromStatus: EmploymentStatus;
+  toStatus: EmploymentStatus;
+  effectiveDate: string; // dd-MM-yyyy
+  changedBy: string;
+  reason?: string;
+  createdAt?: Date;
+}
+
 export type SalaryCode = 'M1' | 'M2' | 'M3' | 'A1' | 'A2' | 'B3' | 'C3' | 'C4';
 
 export interface EmployeeFilter {
@@ -78,6 +105,7 @@ export interface EmployeeFilter {
     min: number;
     max: number;
   };
+  includeArchived?: boolean;
 }
 
 export interface EmployeeFormData extends Omit<Employee, '_id' | 'age' | 'retiredDate' | 'createdAt' | 'updatedAt'> {